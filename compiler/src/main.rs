@@ -26,7 +26,7 @@ use {
         path::PathBuf,
         process::exit as pexit,
     },
-    arcana_core::{ Error, Parser, Result, },
+    arcana_core::{ Error, Escape, Parser, Result, },
 };
 
 const HELP: &str = include_str!("../resources/help.txt");
@@ -39,6 +39,9 @@ struct Options {
     from_string: Option<String>,
     path: Option<PathBuf>,
     quiet: bool,
+    escape: Option<Escape>,
+    now: Option<String>,
+    context_schema: Option<PathBuf>,
 }
 
 impl Options {
@@ -112,6 +115,49 @@ impl Options {
         self.from_string = Some(input.unwrap());
     }
 
+    fn escape(&mut self, args: &mut Args) {
+        if self.escape.is_some() {
+            self.err("escape specified more than once.");
+        }
+
+        let input = args.next();
+        if input.is_none() {
+            self.err("escape requires a value.");
+        }
+
+        self.escape = match input.unwrap().as_str() {
+            "html" => Some(Escape::Html),
+            "none" => Some(Escape::None),
+            mode => self.err(format!("unknown escape mode \"{mode}\"")),
+        };
+    }
+
+    fn now(&mut self, args: &mut Args) {
+        if self.now.is_some() {
+            self.err("now specified more than once.");
+        }
+
+        let input = args.next();
+        if input.is_none() {
+            self.err("now requires a value.");
+        }
+
+        self.now = input;
+    }
+
+    fn context_schema(&mut self, args: &mut Args) {
+        if self.context_schema.is_some() {
+            self.err("context-schema specified more than once.");
+        }
+
+        let input = args.next();
+        if input.is_none() {
+            self.err("context-schema requires a value.");
+        }
+
+        self.context_schema = input.map(|s| s.into());
+    }
+
     fn path(&mut self, path: String) {
         if self.path.is_some() {
             self.err("path specified more than once.");
@@ -188,6 +234,9 @@ fn main() -> Result<()> {
                 "--from-string" => opts.handle_from_string(&mut args),
                 "--version" => opts.version(),
                 "--quiet" => opts.quiet(),
+                "--escape" => opts.escape(&mut args),
+                "--now" => opts.now(&mut args),
+                "--context-schema" => opts.context_schema(&mut args),
                 _ => opts.unknown(arg),
             }
         }
@@ -198,10 +247,13 @@ fn main() -> Result<()> {
             for c in chars {
                 let arg = format!("-{c}");
                 match c {
+                    'c' => opts.context_schema(&mut args),
+                    'e' => opts.escape(&mut args),
                     'h' => opts.help(),
                     'i' => opts.interactive(),
                     'l' => opts.license_notice(),
                     'L' => opts.license(),
+                    'n' => opts.now(&mut args),
                     'q' => opts.quiet(),
                     's' => opts.handle_from_string(&mut args),
                     'V' => opts.version(),
@@ -227,6 +279,18 @@ fn main() -> Result<()> {
         Parser::new(opts.path.unwrap())?
     };
 
+    if let Some(escape) = opts.escape {
+        p.set_escape(escape);
+    }
+
+    if let Some(now) = opts.now {
+        p.set_now(now)?;
+    }
+
+    if let Some(schema) = opts.context_schema {
+        p.set_context_schema(schema)?;
+    }
+
     match p.parse() {
         Ok(_) => print_or_quiet(opts.quiet, p),
         Err(e) => {
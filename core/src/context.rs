@@ -16,24 +16,33 @@
 
 use {
     crate::{
+        date,
         error::{
             Error,
             Result,
         },
-        file::read_file,
+        file::{
+            Source,
+            read_file,
+        },
+        format::Format,
         path::clean_path,
+        schema::Schema,
     },
     std::{
+        cell::OnceCell,
         collections::HashMap,
         fmt::{
             Display,
             Formatter,
             Result as FmtResult,
         },
+        io::Result as IoResult,
         path::{
             Path,
             PathBuf,
         },
+        rc::Rc,
         slice::Iter,
     },
     serde_json::{
@@ -44,6 +53,10 @@ use {
 };
 
 const SCOPESEP: char = '.';
+const KEY_START: char = '[';
+const KEY_END: char = ']';
+
+static NULL: JsonValue = JsonValue::Null;
 
 /// A path to a defined variable.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -70,15 +83,88 @@ impl<S: AsRef<str>> From<S> for Alias {
             vec![]
         }
         else {
-            i.split(SCOPESEP)
-                .map(|seg| seg.to_owned())
-                .collect::<Vec<String>>()
+            split(i)
         };
 
         Self { scope, }
     }
 }
 
+/// Split an alias into its segments, keeping bracketed segments such as the
+/// `[page.lang]` of `translations[page.lang].title` whole.
+fn split(input: &str) -> Vec<String> {
+    let mut scope = Vec::new();
+    let mut segment = String::new();
+    let mut depth = 0;
+    // whether the segment before a separator has already been pushed
+    let mut closed = false;
+
+    for c in input.chars() {
+        match c {
+            SCOPESEP if depth == 0 => {
+                if !closed {
+                    scope.push(std::mem::take(&mut segment));
+                }
+
+                closed = false;
+                continue;
+            },
+            KEY_START if depth == 0 => {
+                if !closed && !segment.is_empty() {
+                    scope.push(std::mem::take(&mut segment));
+                }
+
+                depth += 1;
+            },
+            KEY_START => depth += 1,
+            KEY_END if depth > 0 => {
+                depth -= 1;
+
+                if depth == 0 {
+                    segment.push(c);
+                    scope.push(std::mem::take(&mut segment));
+                    closed = true;
+                    continue;
+                }
+            },
+            _ => {},
+        }
+
+        segment.push(c);
+        closed = false;
+    }
+
+    if !closed {
+        scope.push(segment);
+    }
+
+    scope
+}
+
+/// The alias within a bracketed segment.
+fn dynamic_key(segment: &str) -> Option<&str> {
+    segment.strip_prefix(KEY_START)?.strip_suffix(KEY_END)
+}
+
+/// The position of an item in an array by a segment, if there is one.
+fn position(value: &JsonValue, segment: &str) -> Option<usize> {
+    match value {
+        JsonValue::Array(items) => segment.parse::<usize>().ok()
+            .filter(|idx| *idx < items.len()),
+        _ => None,
+    }
+}
+
+/// Index a value by a segment, with arrays indexed by position.
+fn index<'a>(value: &'a JsonValue, segment: &str) -> &'a JsonValue {
+    match value {
+        JsonValue::Array(items) => position(value, segment)
+            .map(|idx| &items[idx])
+            .unwrap_or(&NULL),
+        value => &value[segment],
+    }
+}
+
 impl<'a> Alias {
     fn iter(&'a self) -> AliasIter<'a> {
         AliasIter::<'a> {
@@ -97,7 +183,7 @@ impl<'a> Iterator for AliasIter<'a> {
 
             return Some(AliasIterItem {
                 segment: segment.to_owned(),
-                alias: self.up_to.join(".").into(),
+                alias: Alias { scope: self.up_to.clone(), },
             });
         }
 
@@ -119,11 +205,72 @@ impl Display for Alias {
     }
 }
 
+/// How arrays are combined in a deep merge.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate)
+enum ArrayMerge {
+    /// The incoming array replaces the existing one.
+    #[default]
+    Replace,
+    /// The incoming items are added after the existing ones.
+    Append,
+    /// Objects sharing a value at the key are merged, with the rest added
+    /// after the existing items.
+    Key(String),
+}
+
+impl ArrayMerge {
+    pub(crate)
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "replace" => Some(Self::Replace),
+            "append" => Some(Self::Append),
+            _ => match name.strip_prefix("key:") {
+                Some(key) if !key.is_empty() => Some(Self::Key(key.to_owned())),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// How null values are treated in a deep merge.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate)
+enum NullMerge {
+    /// An incoming null removes the existing value.
+    #[default]
+    Delete,
+    /// An incoming null is kept as the value.
+    Keep,
+}
+
+impl NullMerge {
+    pub(crate)
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "delete" => Some(Self::Delete),
+            "keep" => Some(Self::Keep),
+            _ => None,
+        }
+    }
+}
+
+/// The rules of a deep merge.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate)
+struct Merge {
+    pub(crate) arrays: ArrayMerge,
+    pub(crate) nulls: NullMerge,
+}
+
 #[derive(Clone, Debug)]
 pub
 struct JsonContext {
     properties: JsonValue,
     scoped_paths: HashMap<Alias, PathBuf>,
+    /// The values of the build, read at the virtual `$build` alias rather
+    /// than from the properties.
+    build: Option<Rc<OnceCell<JsonValue>>>,
 }
 
 impl JsonContext {
@@ -146,6 +293,7 @@ impl JsonContext {
         Ok(Self {
             properties: JsonValue::Object(JsonMap::new()),
             scoped_paths,
+            build: None,
         })
     }
 
@@ -156,12 +304,26 @@ impl JsonContext {
     }
 
     pub(crate)
-    fn read_from_string<P: AsRef<Path>, S: AsRef<str>, A: Into<Alias>>(path: P, source: S, alias: Option<A>) -> Result<Self> {
-        let p: PathBuf = path.as_ref().into();
+    fn read_from_string<P, S, A>(path: P, source: S, alias: Option<A>, format: Format) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        S: AsRef<str>,
+        A: Into<Alias>,
+    {
+        let properties = format.parse(&path, source)?;
+        Self::from_value(path, properties, alias)
+    }
 
-        let mut properties = Self::parse_json(path, source)?;
+    fn from_value<P, A>(path: P, mut properties: JsonValue, alias: Option<A>) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        A: Into<Alias>,
+    {
+        let p: PathBuf = path.as_ref().into();
 
-        if !matches!(properties, JsonValue::Object(_)) {
+        // a document placed at an alias can be any value, such as the rows of
+        // a csv file
+        if alias.is_none() && !matches!(properties, JsonValue::Object(_)) {
             return Err(Error::NotAMap(p));
         };
 
@@ -186,27 +348,171 @@ impl JsonContext {
         Ok(Self {
             properties,
             scoped_paths,
+            build: None,
         })
     }
 
-    fn read_internal<P: AsRef<Path>, A: Into<Alias>>(p: P, alias: Option<A>) -> Result<Self> {
+    /// Read a context, validating the document against the schema before
+    /// it is placed at the alias.
+    pub(crate)
+    fn read_internal<P, A>(p: P, alias: Option<A>, format: Option<Format>, schema: Option<&Schema>) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        A: Into<Alias>,
+    {
         let p = clean_path(p);
 
         if p.is_relative() {
             return Err(Error::IllegalRelativePath(p));
         }
         else if p.is_dir() {
-            return Err(Error::IllegalDirPath(p));
+            return Self::read_dir(p, alias, format, schema);
         }
 
         let file = read_file(&p)?;
+        let format = format.unwrap_or_else(|| Format::from_path(&p));
+        let properties = format.parse(&p, file)?;
+
+        if let Some(schema) = schema {
+            schema.check(&properties, &p)?;
+        }
+
+        Self::from_value(p, properties, alias)
+    }
+
+    /// Read every context file within a directory into an object keyed by
+    /// file stem, with subdirectories read as nested objects. Each file keeps
+    /// its own directory as the scope of relative paths within it.
+    fn read_dir<A>(p: PathBuf, alias: Option<A>, format: Option<Format>, schema: Option<&Schema>) -> Result<Self>
+    where
+        A: Into<Alias>,
+    {
+        let alias: Alias = alias.map(Into::into).unwrap_or_default();
+
+        let mut scoped_paths = HashMap::new();
+        let mut properties = Self::read_dir_value(&p, &alias, format, &mut scoped_paths)?;
+
+        if let Some(schema) = schema {
+            schema.check(&properties, &p)?;
+        }
+
+        for item in alias.reversed().iter() {
+            properties = JsonValue::Object({
+                let mut new_map = JsonMap::new();
+                new_map.insert(item.segment, properties);
+                new_map
+            });
+        }
+
+        scoped_paths.insert(Alias::default(), p);
 
-        Self::read_from_string(p, file, alias)
+        Ok(Self {
+            properties,
+            scoped_paths,
+            build: None,
+        })
     }
 
+    fn read_dir_value(
+        dir: &Path,
+        alias: &Alias,
+        format: Option<Format>,
+        scoped_paths: &mut HashMap<Alias, PathBuf>,
+    ) -> Result<JsonValue> {
+        let mut paths = dir.read_dir()
+            .and_then(|entries| entries.map(|e| e.map(|e| e.path())).collect::<IoResult<Vec<PathBuf>>>())
+            .map_err(|e| Error::IO(e, dir.to_owned()))?;
+
+        // sorted so that a duplicate key is reported the same way every time
+        paths.sort();
+
+        let mut map = JsonMap::new();
+        // the path each key was read from
+        let mut keyed_paths: HashMap<String, PathBuf> = HashMap::new();
+        for path in paths {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+            if name.starts_with('.') {
+                continue;
+            }
+
+            if path.is_dir() {
+                Self::check_key(&name, &path)?;
+                Self::claim_key(&mut keyed_paths, &name, &path)?;
+
+                let mut nested = alias.clone();
+                nested.scope.push(name.clone());
+
+                let value = Self::read_dir_value(&path, &nested, format, scoped_paths)?;
+                map.insert(name, value);
+                continue;
+            }
+
+            // files which are not context files are skipped, unless a format
+            // is given for all of them
+            let file_format = match format {
+                Some(format) => format,
+                None => match path.extension().and_then(|ext| Format::from_name(&ext.to_string_lossy())) {
+                    Some(format) => format,
+                    None => continue,
+                },
+            };
+
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            Self::check_key(&stem, &path)?;
+            Self::claim_key(&mut keyed_paths, &stem, &path)?;
+
+            let value = file_format.parse(&path, read_file(&path)?)?;
+
+            let mut nested = alias.clone();
+            nested.scope.push(stem.clone());
+            scoped_paths.insert(nested, dir.to_owned());
+
+            map.insert(stem, value);
+        }
+
+        Ok(JsonValue::Object(map))
+    }
+
+    /// A key containing a `.` couldn't be reached by an alias, which splits on
+    /// it.
+    fn check_key(key: &str, path: &Path) -> Result<()> {
+        if key.contains('.') {
+            Err(Error::IllegalContextKey(key.to_owned(), path.to_owned()))
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    fn claim_key(keyed_paths: &mut HashMap<String, PathBuf>, key: &str, path: &Path) -> Result<()> {
+        match keyed_paths.get(key) {
+            Some(claimed) => Err(Error::DuplicateContextKey(
+                key.to_owned(), claimed.to_owned(), path.to_owned(),
+            )),
+            None => {
+                keyed_paths.insert(key.to_owned(), path.to_owned());
+                Ok(())
+            },
+        }
+    }
+
+    /// Read a context, parsed by the extension of the file as json, toml,
+    /// yaml or csv, defaulting to json.
     pub
     fn read<P: AsRef<Path>>(p: P) -> Result<Self> {
-        Self::read_internal::<P, Alias>(p, None)
+        Self::read_internal::<P, Alias>(p, None, None, None)
+    }
+
+    /// Read a context, validating it against the JSON Schema at `schema`.
+    pub
+    fn read_with_schema<P, S>(p: P, schema: S) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        S: AsRef<Path>,
+    {
+        let schema = Schema::read(schema)?;
+        Self::read_internal::<P, Alias>(p, None, None, Some(&schema))
     }
 
     pub(crate)
@@ -214,14 +520,16 @@ impl JsonContext {
     where
         P: AsRef<Path>
     {
-        let ctx_map = if let JsonValue::Object(map) = ctx.properties {
+        let JsonContext { properties, mut scoped_paths, .. } = ctx;
+
+        let ctx_map = if let JsonValue::Object(map) = properties {
             map
         }
         else {
             return Err(Error::NotAMap(source_path.as_ref().into()));
         };
 
-        let path_to_scope = if let Some(path) = ctx.scoped_paths.get(&Alias::default()) {
+        let path_to_scope = if let Some(path) = scoped_paths.remove(&Alias::default()) {
             path
         }
         else {
@@ -229,40 +537,207 @@ impl JsonContext {
         };
 
         for (k, v) in ctx_map.into_iter() {
+            Self::unscope_nested(&mut self.scoped_paths, &Alias { scope: vec![k.clone()] });
             self.scoped_paths.insert(k.clone().into(), path_to_scope.to_owned());
             self.properties[&k] = v;
         }
 
+        // values read from a directory keep their own scopes
+        self.scoped_paths.extend(scoped_paths);
+
+        Ok(())
+    }
+
+    /// Merge only the top-level values which aren't already set.
+    pub(crate)
+    fn merge_missing<P>(&mut self, source_path: P, mut ctx: JsonContext) -> Result<()>
+    where
+        P: AsRef<Path>
+    {
+        if let (JsonValue::Object(map), Some(existing)) = (&mut ctx.properties, self.properties.as_object()) {
+            map.retain(|k, _| !existing.contains_key(k));
+        }
+
+        self.merge(source_path, ctx)
+    }
+
+    fn merge_deep<P>(&mut self, source_path: P, ctx: JsonContext, merge: &Merge) -> Result<()>
+    where
+        P: AsRef<Path>
+    {
+        let JsonContext { properties, mut scoped_paths, .. } = ctx;
+
+        if !matches!(properties, JsonValue::Object(_)) {
+            return Err(Error::NotAMap(source_path.as_ref().into()));
+        }
+
+        let path_to_scope = if let Some(path) = scoped_paths.remove(&Alias::default()) {
+            path
+        }
+        else {
+            return Err(Error::NoScopedPath(Alias::default()));
+        };
+
+        Self::merge_value(
+            &mut self.properties,
+            properties,
+            &Alias::default(),
+            &path_to_scope,
+            merge,
+            &mut self.scoped_paths,
+        );
+
+        // values read from a directory keep their own scopes
+        self.scoped_paths.extend(scoped_paths);
+
         Ok(())
     }
 
-    fn read_in_internal<P, A>(&mut self, path: P, alias: Option<A>) -> Result<()>
+    /// Merge `incoming` into `existing` at `alias`, scoping every value which
+    /// is replaced to `dir` while those left in place keep their scopes.
+    fn merge_value(
+        existing: &mut JsonValue,
+        incoming: JsonValue,
+        alias: &Alias,
+        dir: &Path,
+        merge: &Merge,
+        scoped_paths: &mut HashMap<Alias, PathBuf>,
+    ) {
+        match (existing, incoming) {
+            (JsonValue::Object(existing), JsonValue::Object(incoming)) => {
+                for (k, v) in incoming {
+                    let mut nested = alias.clone();
+                    nested.scope.push(k.clone());
+
+                    if v.is_null() && merge.nulls == NullMerge::Delete {
+                        existing.remove(&k);
+                        continue;
+                    }
+
+                    match existing.get_mut(&k) {
+                        Some(value) => Self::merge_value(value, v, &nested, dir, merge, scoped_paths),
+                        None => {
+                            scoped_paths.insert(nested, dir.to_owned());
+                            existing.insert(k, v);
+                        },
+                    }
+                }
+            },
+            (JsonValue::Array(existing), JsonValue::Array(incoming)) => match &merge.arrays {
+                ArrayMerge::Replace => {
+                    Self::unscope_nested(scoped_paths, alias);
+                    scoped_paths.insert(alias.clone(), dir.to_owned());
+                    *existing = incoming;
+                },
+                ArrayMerge::Append => existing.extend(incoming),
+                ArrayMerge::Key(key) => for item in incoming {
+                    let matching = item.get(key)
+                        .filter(|id| !id.is_null())
+                        .and_then(|id| existing.iter_mut().find(|e| e.get(key) == Some(id)));
+
+                    // items aren't addressable by alias, so they keep the
+                    // scope of the array
+                    match matching {
+                        Some(value) => Self::merge_value(value, item, alias, dir, merge, &mut HashMap::new()),
+                        None => existing.push(item),
+                    }
+                },
+            },
+            (existing, incoming) => {
+                Self::unscope_nested(scoped_paths, alias);
+                scoped_paths.insert(alias.clone(), dir.to_owned());
+                *existing = incoming;
+            },
+        }
+    }
+
+    /// Forget the scopes of the values beneath `alias`, which is being
+    /// replaced, so they don't outlive the values they were read with.
+    fn unscope_nested(scoped_paths: &mut HashMap<Alias, PathBuf>, alias: &Alias) {
+        scoped_paths.retain(|a, _| a.scope.len() <= alias.scope.len() || !a.scope.starts_with(&alias.scope));
+    }
+
+    fn read_in_internal<P, A>(&mut self, path: P, alias: Option<A>, format: Option<Format>, schema: Option<&Schema>) -> Result<()>
     where
         P: AsRef<Path>,
         A: Into<Alias>
     {
-        let ctx = Self::read_internal(path.as_ref(), alias)?;
+        let ctx = Self::read_internal(path.as_ref(), alias, format, schema)?;
 
         self.merge(path, ctx)
     }
 
     pub(crate)
-    fn read_in<P: AsRef<Path>>(&mut self, p: P) -> Result<()> {
-        self.read_in_internal::<P, Alias>(p, None)
+    fn read_in<P: AsRef<Path>>(&mut self, p: P, format: Option<Format>, schema: Option<&Schema>) -> Result<()> {
+        self.read_in_internal::<P, Alias>(p, None, format, schema)
     }
 
     pub(crate)
-    fn read_as<P: AsRef<Path>, A: Into<Alias>>(p: P, alias: A) -> Result<Self> {
-        Self::read_internal(p, Some(alias))
+    fn read_as<P, A>(p: P, alias: A, format: Option<Format>, schema: Option<&Schema>) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        A: Into<Alias>
+    {
+        Self::read_internal(p, Some(alias), format, schema)
+    }
+
+    pub(crate)
+    fn read_in_as<P, A>(&mut self, p: P, alias: A, format: Option<Format>, schema: Option<&Schema>) -> Result<()>
+    where
+        P: AsRef<Path>,
+        A: Into<Alias>
+    {
+        self.read_in_internal(p, Some(alias), format, schema)
     }
 
+    /// Read a context, merging it deeply into this one.
     pub(crate)
-    fn read_in_as<P, A>(&mut self, p: P, alias: A) -> Result<()>
+    fn read_in_merged<P, A>(
+        &mut self, path: P, alias: Option<A>, format: Option<Format>, schema: Option<&Schema>,
+        merge: &Merge,
+    ) -> Result<()>
     where
         P: AsRef<Path>,
         A: Into<Alias>
     {
-        self.read_in_internal(p, Some(alias))
+        let ctx = Self::read_internal(path.as_ref(), alias, format, schema)?;
+
+        self.merge_deep(path, ctx, merge)
+    }
+
+    /// Read the front matter of a source, written as json when it begins
+    /// with `{` and as toml otherwise, leaving the source positioned after it.
+    pub(crate)
+    fn read_front_matter(source: &mut Source) -> Result<Option<Self>> {
+        let matter = match source.front_matter() {
+            Some(matter) => matter,
+            None => return Ok(None),
+        };
+
+        let format = if matter.trim_start().starts_with('{') {
+            Format::Json
+        }
+        else {
+            Format::Toml
+        };
+
+        Self::read_from_string::<_, _, Alias>(source.file(), matter, None, format)
+            .map(Some)
+            // the lines of the front matter follow the opening line
+            .map_err(|e| match e {
+                Error::SourceParse(format, msg, line, p) => Error::SourceParse(format, msg, line + 1, p),
+                e => e,
+            })
+    }
+
+    pub(crate)
+    fn into_value(self) -> JsonValue {
+        self.properties
+    }
+
+    pub(crate)
+    fn read_format<P: AsRef<Path>>(p: P, format: Option<Format>, schema: Option<&Schema>) -> Result<Self> {
+        Self::read_internal::<P, Alias>(p, None, format, schema)
     }
 
     pub(crate)
@@ -271,16 +746,29 @@ impl JsonContext {
         A: Into<Alias>
     {
         let a: Alias = alias.into();
+        let a = match self.resolve(&a) {
+            Ok(Some(a)) => a,
+            _ => return,
+        };
 
         let mut value = &mut self.properties;
         let len = a.scope.len();
         for (idx, seg) in a.iter().enumerate() {
-            if let None|Some(JsonValue::Null) = value.get(&seg.segment) {
+            if let JsonValue::Null = index(value, &seg.segment) {
                 return;
             }
 
+            let item = position(value, &seg.segment);
+
             if idx != len - 1 {
-                value = value.get_mut(&seg.segment).unwrap();
+                value = match item {
+                    Some(item) => &mut value[item],
+                    None => value.get_mut(&seg.segment).unwrap(),
+                };
+            }
+            else if let (Some(item), JsonValue::Array(items)) = (item, &mut *value) {
+                items.remove(item);
+                return;
             }
             else if value.is_object() {
                 value.as_object_mut().unwrap().remove(&seg.segment);
@@ -295,14 +783,40 @@ impl JsonContext {
         A: Into<Alias>
     {
         let a: Alias =  alias.into();
+        let a = match self.resolve(&a)? {
+            Some(a) => a,
+            None => return Err(Error::UnresolvedAlias(a)),
+        };
 
         let mut value = &mut self.properties;
         let len = a.scope.len();
         for (idx, seg) in a.iter().enumerate() {
-            // not the last iteration, make sure the segment is an object
+            // items of an array are set in place, and an array is never
+            // replaced by an object
+            if value.is_array() {
+                let item = match position(value, &seg.segment) {
+                    Some(item) => item,
+                    None => return Err(Error::UnresolvedAlias(a.clone())),
+                };
+
+                if idx != len - 1 {
+                    if !value[item].is_object() && !value[item].is_array() {
+                        value[item] = JsonValue::Object(JsonMap::new());
+                    }
+
+                    value = &mut value[item];
+                    continue;
+                }
+
+                value[item] = val;
+                self.scoped_paths.insert(a, set_from_dir);
+                break;
+            }
+
+            // not the last iteration, make sure the segment is an object or array
             if idx != len - 1 {
-                // if the value is not an object
-                if !matches!(value.get(&seg.segment), Some(JsonValue::Object(_))) {
+                // if the value is not an object or array
+                if !matches!(value.get(&seg.segment), Some(JsonValue::Object(_) | JsonValue::Array(_))) {
                     value.as_object_mut().unwrap()
                         .insert(
                             seg.segment.to_owned(),
@@ -324,19 +838,80 @@ impl JsonContext {
         Ok(())
     }
 
+    /// Resolve the bracketed segments of an alias to the keys they refer to,
+    /// and negative indices to positions from the start of their arrays, so
+    /// that a value has one alias however it is written. `None` when a key
+    /// isn't a string or number, or an index is out of bounds.
+    fn resolve(&self, a: &Alias) -> Result<Option<Alias>> {
+        let mut scope = Vec::with_capacity(a.scope.len());
+
+        let (mut value, skip) = self.root(a);
+        scope.extend(a.scope.iter().take(skip).cloned());
+
+        for segment in a.scope.iter().skip(skip) {
+            let mut segment = match dynamic_key(segment) {
+                Some(key) => match self.get_internal(key)?.0 {
+                    JsonValue::String(key) => key.to_owned(),
+                    JsonValue::Number(key) => key.to_string(),
+                    _ => return Ok(None),
+                },
+                None => segment.to_owned(),
+            };
+
+            if let (JsonValue::Array(items), Some(from_end)) = (value, segment.strip_prefix('-')) {
+                segment = match from_end.parse::<usize>().ok().and_then(|n| items.len().checked_sub(n)) {
+                    Some(idx) if idx < items.len() => idx.to_string(),
+                    _ => return Ok(None),
+                };
+            }
+
+            value = index(value, &segment);
+            scope.push(segment);
+        }
+
+        Ok(Some(Alias { scope, }))
+    }
+
+    /// The value an alias is read from and the number of its segments naming
+    /// it, the build values for the virtual `$build` alias and the properties
+    /// otherwise.
+    fn root(&self, a: &Alias) -> (&JsonValue, usize) {
+        match a.scope.first() {
+            Some(segment) if segment == crate::parser::consts::BUILD => (
+                self.build.as_ref().and_then(|b| b.get()).unwrap_or(&NULL),
+                1,
+            ),
+            _ => (&self.properties, 0),
+        }
+    }
+
+    /// Read the virtual `$build` alias from the given values.
+    pub(crate)
+    fn set_build(&mut self, build: Rc<OnceCell<JsonValue>>) {
+        self.build = Some(build);
+    }
+
     fn get_internal<A: Into<Alias>>(&self, alias: A) -> Result<(&JsonValue, PathBuf)> {
         // default scoped path
         let mut path = self.scoped_paths.get(&Alias::default());
 
         let a: Alias = alias.into();
 
-        let mut value = &self.properties;
-        for item in a.iter() {
+        let resolved = match self.resolve(&a)? {
+            Some(resolved) => resolved,
+            None => return match path {
+                Some(abs_path) => Ok((&NULL, abs_path.to_owned())),
+                None => Err(Error::NoScopedPath(a)),
+            },
+        };
+
+        let (mut value, skip) = self.root(&resolved);
+        for item in resolved.iter().skip(skip) {
             if let Some(abs_path) = self.scoped_paths.get(&item.alias) {
                 path = Some(abs_path);
             }
 
-            value = &value[&item.segment];
+            value = index(value, &item.segment);
 
             if let JsonValue::Null = value {
                 break;
@@ -372,6 +947,7 @@ impl JsonContext {
         Ok(Self {
             properties: value.clone(),
             scoped_paths,
+            build: self.build.clone(),
         })
     }
 
@@ -410,6 +986,7 @@ impl JsonContext {
                     Self {
                         properties: v,
                         scoped_paths,
+                        build: self.build.clone(),
                     }
                 })
                 .collect::<Vec<Self>>()
@@ -517,6 +1094,58 @@ impl JsonContext {
         }
     }
 
+    /// Read the entries of an object, in the order they were written, as
+    /// objects holding each `key` and `value`.
+    fn get_entries_internal<A>(&self, alias: A, as_paths: bool, nullable: bool) -> Result<Vec<JsonValue>>
+    where
+        A: Into<Alias>
+    {
+        let a = alias.into();
+        let (val, abs_path,) = self.get_internal(a.clone())?;
+
+        match val {
+            JsonValue::Object(map) => map.iter()
+                .map(|(key, value)| {
+                    let value = if !as_paths {
+                        value.clone()
+                    }
+                    else if let JsonValue::String(p) = value {
+                        Self::normalize_path(abs_path.to_owned(), p.into())
+                            .to_str()
+                            .unwrap_or("")
+                            .into()
+                    }
+                    else {
+                        return Err(Error::ValuesNotPath(a.clone()));
+                    };
+
+                    let mut entry = JsonMap::new();
+                    entry.insert("key".to_owned(), key.to_owned().into());
+                    entry.insert("value".to_owned(), value);
+                    Ok(JsonValue::Object(entry))
+                })
+                .collect(),
+            JsonValue::Null if nullable => Ok(vec![]),
+            _ => Err(Error::ValueNotObject(a)),
+        }
+    }
+
+    pub(crate)
+    fn get_entries<A>(&mut self, alias: A, as_paths: bool) -> Result<Vec<JsonValue>>
+    where
+        A: Into<Alias>
+    {
+        self.get_entries_internal(alias, as_paths, false)
+    }
+
+    pub(crate)
+    fn get_entries_opt<A>(&mut self, alias: A, as_paths: bool) -> Result<Vec<JsonValue>>
+    where
+        A: Into<Alias>
+    {
+        self.get_entries_internal(alias, as_paths, true)
+    }
+
     pub(crate)
     fn get_array<A>(&mut self, alias: A) -> Result<Vec<JsonValue>>
     where
@@ -598,7 +1227,7 @@ impl JsonContext {
 
     pub(crate)
     fn ne<A: Into<Alias>, B: Into<Alias>>(&self, a: A, b: B) -> Result<bool> {
-        Ok(self.get(a)?.ne(self.get(b)?))
+        Ok(!self.eq(a, b)?)
     }
 
     pub(crate)
@@ -609,7 +1238,7 @@ impl JsonContext {
         let b_val = self.get(b.clone())?;
 
         match (a_val, b_val) {
-            (JsonValue::String(a), JsonValue::String(b)) => Ok(a.gt(b)),
+            (JsonValue::String(a), JsonValue::String(b)) => Ok(date::compare(a, b).is_gt()),
             (JsonValue::Number(a), JsonValue::Number(b)) => if a.is_i64() && b.is_i64() {
                 Ok(a.as_i64().unwrap().gt(&b.as_i64().unwrap()))
             }
@@ -632,7 +1261,7 @@ impl JsonContext {
         let b_val = self.get(b.clone())?;
 
         match (a_val, b_val) {
-            (JsonValue::String(a), JsonValue::String(b)) => Ok(a.ge(b)),
+            (JsonValue::String(a), JsonValue::String(b)) => Ok(date::compare(a, b).is_ge()),
             (JsonValue::Number(a), JsonValue::Number(b)) => if a.is_i64() && b.is_i64() {
                 Ok(a.as_i64().unwrap().ge(&b.as_i64().unwrap()))
             }
@@ -655,7 +1284,7 @@ impl JsonContext {
         let b_val = self.get(b.clone())?;
 
         match (a_val, b_val) {
-            (JsonValue::String(a), JsonValue::String(b)) => Ok(a.lt(b)),
+            (JsonValue::String(a), JsonValue::String(b)) => Ok(date::compare(a, b).is_lt()),
             (JsonValue::Number(a), JsonValue::Number(b)) => if a.is_i64() && b.is_i64() {
                 Ok(a.as_i64().unwrap().lt(&b.as_i64().unwrap()))
             }
@@ -678,7 +1307,7 @@ impl JsonContext {
         let b_val = self.get(b.clone())?;
 
         match (a_val, b_val) {
-            (JsonValue::String(a), JsonValue::String(b)) => Ok(a.le(b)),
+            (JsonValue::String(a), JsonValue::String(b)) => Ok(date::compare(a, b).is_le()),
             (JsonValue::Number(a), JsonValue::Number(b)) => if a.is_i64() && b.is_i64() {
                 Ok(a.as_i64().unwrap().le(&b.as_i64().unwrap()))
             }
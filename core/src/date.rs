@@ -0,0 +1,477 @@
+//! Dates and times for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    cmp::Ordering,
+    env::var,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August",
+    "September", "October", "November", "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday",
+    "Saturday",
+];
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// The environment variable which fixes the build date for reproducible
+/// builds.
+const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// A calendar date with an optional time of day and UTC offset, as written in
+/// RFC 3339.
+#[derive(Clone, Debug)]
+pub(crate)
+struct DateTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    nanos: u32,
+    /// The offset from UTC in minutes, a missing offset being treated as UTC.
+    offset: Option<i32>,
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4|6|9|11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days between 1970-01-01 and a date in the proleptic
+/// Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+/// The date falling a number of days after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+/// Read exactly `len` ascii digits from the start of `s`.
+fn digits(s: &str, len: usize) -> Option<(u32, &str)> {
+    let head = s.get(..len)?;
+
+    if !head.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((head.parse().ok()?, &s[len..]))
+}
+
+impl DateTime {
+    /// Read an RFC 3339 timestamp or a plain `YYYY-MM-DD` date. The seconds
+    /// of a timestamp and its offset may be omitted.
+    pub(crate)
+    fn parse<S>(value: S) -> Option<Self>
+    where
+        S: AsRef<str>
+    {
+        let s = value.as_ref().trim();
+
+        let (year, s) = digits(s, 4)?;
+        let s = s.strip_prefix('-')?;
+        let (month, s) = digits(s, 2)?;
+        let s = s.strip_prefix('-')?;
+        let (day, s) = digits(s, 2)?;
+
+        if !(1..=12).contains(&month) || !(1..=days_in_month(year as i64, month)).contains(&day) {
+            return None;
+        }
+
+        let mut date = Self {
+            year: year as i64,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            nanos: 0,
+            offset: None,
+        };
+
+        if s.is_empty() {
+            return Some(date);
+        }
+
+        let s = s.strip_prefix([ 'T', 't', ' ', ])?;
+        let (hour, s) = digits(s, 2)?;
+        let s = s.strip_prefix(':')?;
+        let (minute, mut s) = digits(s, 2)?;
+
+        if let Some(rest) = s.strip_prefix(':') {
+            let (second, rest) = digits(rest, 2)?;
+            date.second = second;
+            s = rest;
+
+            if let Some(rest) = s.strip_prefix('.') {
+                let len = rest.bytes().take_while(|b| b.is_ascii_digit()).count();
+                if len == 0 {
+                    return None;
+                }
+
+                let frac = &rest[..len.min(9)];
+                date.nanos = frac.parse::<u32>().ok()? * 10_u32.pow(9 - frac.len() as u32);
+                s = &rest[len..];
+            }
+        }
+
+        // a leap second is allowed
+        if hour > 23 || minute > 59 || date.second > 60 {
+            return None;
+        }
+
+        date.hour = hour;
+        date.minute = minute;
+
+        date.offset = match s {
+            "" => None,
+            "Z"|"z" => Some(0),
+            s => {
+                let (sign, s) = match s.strip_prefix('+') {
+                    Some(s) => (1, s),
+                    None => (-1, s.strip_prefix('-')?),
+                };
+
+                let (hours, s) = digits(s, 2)?;
+                let s = s.strip_prefix(':')?;
+                let (minutes, s) = digits(s, 2)?;
+
+                if !s.is_empty() || hours > 23 || minutes > 59 {
+                    return None;
+                }
+
+                Some(sign * (hours * 60 + minutes) as i32)
+            },
+        };
+
+        Some(date)
+    }
+
+    /// Create a UTC timestamp from the seconds elapsed since 1970-01-01.
+    pub(crate)
+    fn from_unix(seconds: i64) -> Self {
+        let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+        let time = seconds.rem_euclid(SECONDS_PER_DAY);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: (time / 3600) as u32,
+            minute: (time % 3600 / 60) as u32,
+            second: (time % 60) as u32,
+            nanos: 0,
+            offset: Some(0),
+        }
+    }
+
+    /// The date of the build, read from `SOURCE_DATE_EPOCH` when it is set
+    /// and otherwise from the system clock.
+    pub(crate)
+    fn build_now() -> Self {
+        let seconds = var(SOURCE_DATE_EPOCH).ok()
+            .and_then(|s| s.trim().parse::<i64>().ok())
+            .unwrap_or_else(|| SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0));
+
+        Self::from_unix(seconds)
+    }
+
+    fn days(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    /// The seconds elapsed since 1970-01-01 in UTC.
+    fn unix(&self) -> i64 {
+        self.days() * SECONDS_PER_DAY +
+            self.hour as i64 * 3600 +
+            self.minute as i64 * 60 +
+            self.second as i64 -
+            self.offset.unwrap_or(0) as i64 * 60
+    }
+
+    /// Write the date as an RFC 3339 timestamp.
+    pub(crate)
+    fn to_rfc3339(&self) -> String {
+        let format = if self.nanos == 0 {
+            "%Y-%m-%dT%H:%M:%S%:z"
+        }
+        else {
+            "%Y-%m-%dT%H:%M:%S.%f%:z"
+        };
+
+        self.format(&DateFormat::parse(format).unwrap())
+    }
+
+    /// Write the date using a parsed format.
+    pub(crate)
+    fn format(&self, format: &DateFormat) -> String {
+        let mut output = String::new();
+
+        for item in format.0.iter() {
+            let hour12 = match self.hour % 12 {
+                0 => 12,
+                h => h,
+            };
+
+            let s = match item {
+                FormatItem::Literal(s) => s.to_owned(),
+                FormatItem::Year => format!("{:04}", self.year),
+                FormatItem::ShortYear => format!("{:02}", self.year.rem_euclid(100)),
+                FormatItem::Month(true) => format!("{:02}", self.month),
+                FormatItem::Month(false) => self.month.to_string(),
+                FormatItem::MonthName => MONTHS[self.month as usize - 1].to_owned(),
+                FormatItem::MonthAbbr => MONTHS[self.month as usize - 1][..3].to_owned(),
+                FormatItem::Day(true) => format!("{:02}", self.day),
+                FormatItem::Day(false) => self.day.to_string(),
+                FormatItem::DayOfYear => format!(
+                    "{:03}",
+                    self.days() - days_from_civil(self.year, 1, 1) + 1
+                ),
+                FormatItem::Weekday => WEEKDAYS[self.weekday()].to_owned(),
+                FormatItem::WeekdayAbbr => WEEKDAYS[self.weekday()][..3].to_owned(),
+                FormatItem::Hour(true) => format!("{:02}", self.hour),
+                FormatItem::Hour(false) => self.hour.to_string(),
+                FormatItem::Hour12(true) => format!("{:02}", hour12),
+                FormatItem::Hour12(false) => hour12.to_string(),
+                FormatItem::Meridiem => if self.hour < 12 { "AM" } else { "PM" }.to_owned(),
+                FormatItem::Minute => format!("{:02}", self.minute),
+                FormatItem::Second => format!("{:02}", self.second),
+                FormatItem::Fraction => format!("{:09}", self.nanos)
+                    .trim_end_matches('0')
+                    .to_owned(),
+                FormatItem::Offset(colon) => {
+                    let offset = self.offset.unwrap_or(0);
+                    let sign = if offset < 0 { '-' } else { '+' };
+                    let offset = offset.abs();
+
+                    if *colon {
+                        format!("{sign}{:02}:{:02}", offset / 60, offset % 60)
+                    }
+                    else {
+                        format!("{sign}{:02}{:02}", offset / 60, offset % 60)
+                    }
+                },
+                FormatItem::Unix => self.unix().to_string(),
+            };
+
+            output.push_str(&s);
+        }
+
+        output
+    }
+
+    /// The day of the week, counted from Sunday.
+    fn weekday(&self) -> usize {
+        // 1970-01-01 was a thursday
+        (self.days() + 4).rem_euclid(7) as usize
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.unix(), self.nanos).cmp(&(other.unix(), other.nanos))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Literal(String),
+    Year,
+    ShortYear,
+    /// The month number, padded with a zero when true.
+    Month(bool),
+    MonthName,
+    MonthAbbr,
+    /// The day of the month, padded with a zero when true.
+    Day(bool),
+    DayOfYear,
+    Weekday,
+    WeekdayAbbr,
+    /// The hour on a 24-hour clock, padded with a zero when true.
+    Hour(bool),
+    /// The hour on a 12-hour clock, padded with a zero when true.
+    Hour12(bool),
+    Meridiem,
+    Minute,
+    Second,
+    Fraction,
+    /// The UTC offset, separated by a colon when true.
+    Offset(bool),
+    Unix,
+}
+
+/// A date format made up of `%` directives and literal text.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate)
+struct DateFormat(Vec<FormatItem>);
+
+impl DateFormat {
+    /// Read a date format, returning `None` if it contains an unknown
+    /// directive.
+    pub(crate)
+    fn parse<S>(format: S) -> Option<Self>
+    where
+        S: AsRef<str>
+    {
+        let mut items = Vec::new();
+        let mut literal = String::new();
+        let mut chars = format.as_ref().chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+
+            let (pad, c) = match chars.next()? {
+                '-' => (false, chars.next()?),
+                ':' => match chars.next()? {
+                    'z' => (true, ':'),
+                    _ => return None,
+                },
+                c => (true, c),
+            };
+
+            if (pad, c) == (true, '%') {
+                literal.push('%');
+                continue;
+            }
+
+            if !literal.is_empty() {
+                items.push(FormatItem::Literal(std::mem::take(&mut literal)));
+            }
+
+            let item = match (pad, c) {
+                (true, 'F') => {
+                    items.extend(Self::parse("%Y-%m-%d")?.0);
+                    continue;
+                },
+                (true, 'T') => {
+                    items.extend(Self::parse("%H:%M:%S")?.0);
+                    continue;
+                },
+                (true, 'Y') => FormatItem::Year,
+                (true, 'y') => FormatItem::ShortYear,
+                (pad, 'm') => FormatItem::Month(pad),
+                (true, 'B') => FormatItem::MonthName,
+                (true, 'b') => FormatItem::MonthAbbr,
+                (pad, 'd') => FormatItem::Day(pad),
+                (true, 'j') => FormatItem::DayOfYear,
+                (true, 'A') => FormatItem::Weekday,
+                (true, 'a') => FormatItem::WeekdayAbbr,
+                (pad, 'H') => FormatItem::Hour(pad),
+                (pad, 'I') => FormatItem::Hour12(pad),
+                (true, 'p') => FormatItem::Meridiem,
+                (true, 'M') => FormatItem::Minute,
+                (true, 'S') => FormatItem::Second,
+                (true, 'f') => FormatItem::Fraction,
+                (true, 'z') => FormatItem::Offset(false),
+                (true, ':') => FormatItem::Offset(true),
+                (true, 's') => FormatItem::Unix,
+                _ => return None,
+            };
+
+            items.push(item);
+        }
+
+        if !literal.is_empty() {
+            items.push(FormatItem::Literal(literal));
+        }
+
+        Some(Self(items))
+    }
+}
+
+/// Compare two strings, every date coming before every other string. Dates
+/// are ordered by the instant they name and then lexically, so that the
+/// order is total, and other strings lexically.
+pub(crate)
+fn compare<A, B>(a: A, b: B) -> Ordering
+where
+    A: AsRef<str>,
+    B: AsRef<str>,
+{
+    let (a, b) = (a.as_ref(), b.as_ref());
+
+    match (DateTime::parse(a), DateTime::parse(b)) {
+        (Some(a_date), Some(b_date)) => a_date.cmp(&b_date).then_with(|| a.cmp(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Format a date with a parsed format.
+pub(crate)
+fn format<S>(value: S, format: &DateFormat) -> Option<String>
+where
+    S: AsRef<str>
+{
+    Some(DateTime::parse(value)?.format(format))
+}
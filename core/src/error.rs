@@ -41,7 +41,10 @@ pub enum Error {
     IllegalRelativePath(PathBuf),
     IllegalDirPath(PathBuf),
     NoScopedPath(Alias),
+    UnresolvedAlias(Alias),
     NotAMap(PathBuf),
+    DuplicateContextKey(String, PathBuf, PathBuf),
+    IllegalContextKey(String, PathBuf),
     UnterminatedTag(String, Coordinate, PathBuf),
     IllegalCharacter(String, char, Coordinate, PathBuf),
     IllegalCharacterAfterExtends(char, Coordinate, PathBuf),
@@ -53,13 +56,36 @@ pub enum Error {
     EmptyAlias(Coordinate, PathBuf),
     ValueNotArray(Alias),
     ValueNotString(Alias),
+    ValueNotNumber(Alias),
+    ValueNotDate(Alias),
     ValueNotPath(Alias),
     ValuesNotPath(Alias),
     ValueNotFound(Alias),
     ValueNotObject(Alias),
     ContextEmpty(Coordinate, PathBuf),
     IllegalSplit(usize, usize, Coordinate, PathBuf),
+    IllegalArgument(String, String, Coordinate, PathBuf),
     CannotCompare(Alias, Alias),
+    IllegalDate(String),
+    IllegalLoopControl(String, Coordinate, PathBuf),
+    UnknownMacro(String, Coordinate, PathBuf),
+    UnknownParameter(String, String, Coordinate, PathBuf),
+    MissingParameter(String, String, Coordinate, PathBuf),
+    ReservedMacroName(String, Coordinate, PathBuf),
+    MacroDepth(String, usize, Coordinate, PathBuf),
+    CircularImport(PathBuf, Coordinate, PathBuf),
+    MissingTemplateParameter(String, PathBuf, Coordinate, PathBuf),
+    TemplateParameterType(String, String, PathBuf, Coordinate, PathBuf),
+    SchemaViolation(Vec<(String, String)>, PathBuf),
+    SourceSchemaViolation(Vec<(String, String)>, PathBuf, Coordinate, PathBuf),
+    SourceParse(String, String, usize, PathBuf),
+}
+
+fn violations(v: &[(String, String)]) -> String {
+    v.iter()
+        .map(|(pointer, msg)| format!("{pointer:?} {msg}"))
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
 impl Display for Error {
@@ -76,9 +102,23 @@ impl Display for Error {
             Self::NoScopedPath(a) => fmtr.write_fmt(
                 format_args!("No scoped path found in alias {}", a)
             ),
+            Self::UnresolvedAlias(a) => fmtr.write_fmt(
+                format_args!("Alias {} has a key or index which could not be resolved", a)
+            ),
             Self::NotAMap(p) => fmtr.write_fmt(
                 format_args!("Context at {:?} was not a json object", p)
             ),
+            Self::DuplicateContextKey(key, p1, p2) => fmtr.write_fmt(format_args!(
+                "Context key {:?} is read from both {:?} and {:?}",
+                key,
+                p1,
+                p2,
+            )),
+            Self::IllegalContextKey(key, p) => fmtr.write_fmt(format_args!(
+                "Context key {:?} read from {:?} can't contain a '.'",
+                key,
+                p,
+            )),
             Self::UnterminatedTag(name, c, p) => fmtr.write_fmt(format_args!(
                 "Unterminated {} in {:?} at line {} position {}",
                 name,
@@ -146,6 +186,14 @@ impl Display for Error {
                 "Value at {} was not a string",
                 a
             )),
+            Self::ValueNotNumber(a) => fmtr.write_fmt(format_args!(
+                "Value at {} was not a number",
+                a
+            )),
+            Self::ValueNotDate(a) => fmtr.write_fmt(format_args!(
+                "Value at {} was not a date",
+                a
+            )),
             Self::ValueNotPath(a) => fmtr.write_fmt(format_args!(
                 "Value at {} was not a path",
                 a
@@ -174,9 +222,107 @@ impl Display for Error {
                 c.line() + 1,
                 c.position() + 1
             )),
+            Self::IllegalArgument(name, arg, c, f) => fmtr.write_fmt(format_args!(
+                "Argument {arg:?} was invalid for {name} modifier in {:?} at line {} position {}",
+                f,
+                c.line() + 1,
+                c.position() + 1
+            )),
             Self::CannotCompare(a, b) => fmtr.write_fmt(format_args!(
                 "Cannot compare non-similar data-type {a} to {b}"
             )),
+            Self::IllegalDate(d) => fmtr.write_fmt(format_args!(
+                "Date {d:?} was not an RFC 3339 timestamp or date"
+            )),
+            Self::IllegalLoopControl(name, c, p) => fmtr.write_fmt(format_args!(
+                "The {} tag was not within a loop in {:?} at line {} position {}",
+                name,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::UnknownMacro(name, c, p) => fmtr.write_fmt(format_args!(
+                "Macro {} was not defined or imported in {:?} at line {} position {}",
+                name,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::UnknownParameter(name, param, c, p) => fmtr.write_fmt(format_args!(
+                "Macro {} has no parameter {} in {:?} at line {} position {}",
+                name,
+                param,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::MissingParameter(name, param, c, p) => fmtr.write_fmt(format_args!(
+                "Macro {} was called without parameter {} in {:?} at line {} position {}",
+                name,
+                param,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::ReservedMacroName(name, c, p) => fmtr.write_fmt(format_args!(
+                "Macro name {} is reserved by the macro tag in {:?} at line {} position {}",
+                name,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::MacroDepth(name, depth, c, p) => fmtr.write_fmt(format_args!(
+                "Macro {} exceeded the maximum call depth of {} in {:?} at line {} position {}",
+                name,
+                depth,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::CircularImport(i, c, p) => fmtr.write_fmt(format_args!(
+                "Circular import of {:?} in {:?} at line {} position {}",
+                i,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::MissingTemplateParameter(param, t, c, p) => fmtr.write_fmt(format_args!(
+                "Template {:?} requires parameter {} in {:?} at line {} position {}",
+                t,
+                param,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::TemplateParameterType(param, ty, t, c, p) => fmtr.write_fmt(format_args!(
+                "Parameter {} of template {:?} was not of type {} in {:?} at line {} position {}",
+                param,
+                t,
+                ty,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+            )),
+            Self::SourceParse(format, msg, line, p) => fmtr.write_fmt(format_args!(
+                "{} error in {:?} at line {}: {}",
+                format,
+                p,
+                line + 1,
+                msg,
+            )),
+            Self::SchemaViolation(v, d) => fmtr.write_fmt(format_args!(
+                "Context {:?} did not match its schema: {}",
+                d,
+                violations(v),
+            )),
+            Self::SourceSchemaViolation(v, d, c, p) => fmtr.write_fmt(format_args!(
+                "Context {:?} sourced in {:?} at line {} position {} did not match its schema: {}",
+                d,
+                p,
+                c.line() + 1,
+                c.position() + 1,
+                violations(v),
+            )),
         }
     }
 }
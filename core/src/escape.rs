@@ -0,0 +1,223 @@
+//! Output escaping for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::Path;
+
+/// The escaping applied to values written by the include-content tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub
+enum Escape {
+    /// Values are written verbatim.
+    None,
+    /// Values are escaped for use within an HTML document.
+    Html,
+    /// Values are escaped for use within an HTML attribute.
+    Attr,
+    /// Values are percent-encoded for use within a URL.
+    Url,
+    /// Values are escaped for use within a JavaScript string.
+    Js,
+    /// Values are quoted for use as a CSV field.
+    Csv,
+    /// Values are quoted for use as a single shell word.
+    Sh,
+}
+
+impl Escape {
+    /// Determine the default escaping for a template by its extension.
+    pub(crate)
+    fn from_path<P>(p: P) -> Self
+    where
+        P: AsRef<Path>
+    {
+        match p.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("html")|Some("htm") => Self::Html,
+            _ => Self::None,
+        }
+    }
+
+    /// Escape a stringlike value.
+    pub(crate)
+    fn escape<S>(&self, value: S) -> String
+    where
+        S: AsRef<str>
+    {
+        match self {
+            Self::None => value.as_ref().to_owned(),
+            Self::Html => html(value),
+            Self::Attr => attr(value),
+            Self::Url => url(value),
+            Self::Js => js(value),
+            Self::Csv => csv(value),
+            Self::Sh => sh(value),
+        }
+    }
+
+    /// Escape a serialized json value.
+    pub(crate)
+    fn escape_json<S>(&self, value: S) -> String
+    where
+        S: AsRef<str>
+    {
+        match self {
+            Self::None => value.as_ref().to_owned(),
+            Self::Html|Self::Js => json_html(value),
+            _ => self.escape(value),
+        }
+    }
+}
+
+/// Escape the characters with special meaning in HTML text and attributes.
+pub(crate)
+fn html<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let mut output = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Escape serialized json so it cannot close an HTML element while remaining
+/// valid json.
+pub(crate)
+fn json_html<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let mut output = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => output.push_str("\\u0026"),
+            '<' => output.push_str("\\u003c"),
+            '>' => output.push_str("\\u003e"),
+            '\'' => output.push_str("\\u0027"),
+            '\u{2028}' => output.push_str("\\u2028"),
+            '\u{2029}' => output.push_str("\\u2029"),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Escape every ASCII character which is not alphanumeric as a hexadecimal
+/// character reference so the value is safe within quoted or unquoted
+/// attributes.
+pub(crate)
+fn attr<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let mut output = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if c.is_ascii() && !c.is_ascii_alphanumeric() {
+            output.push_str(&format!("&#x{:02X};", c as u32));
+        }
+        else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+/// Percent-encode the UTF-8 bytes of every character outside of the
+/// unreserved set.
+pub(crate)
+fn url<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let mut output = String::with_capacity(value.len());
+
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z'|b'a'..=b'z'|b'0'..=b'9'|b'-'|b'_'|b'.'|b'~' => {
+                output.push(b as char);
+            },
+            b => output.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    output
+}
+
+/// Escape every ASCII character which is not alphanumeric as a hexadecimal
+/// escape sequence, along with the unicode line terminators, so the value is
+/// safe within any quoted JavaScript string.
+pub(crate)
+fn js<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let mut output = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\u{2028}' => output.push_str("\\u2028"),
+            '\u{2029}' => output.push_str("\\u2029"),
+            c if c.is_ascii() && !c.is_ascii_alphanumeric() => {
+                output.push_str(&format!("\\x{:02X}", c as u32));
+            },
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Quote a CSV field when it contains a delimiter, quote, or line break.
+pub(crate)
+fn csv<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+
+    if !value.contains([ ',', '"', '\r', '\n', ]) {
+        return value.to_owned();
+    }
+
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// Single-quote a value so a POSIX shell reads it as one literal word.
+pub(crate)
+fn sh<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    format!("'{}'", value.as_ref().replace('\'', "'\\''"))
+}
@@ -21,7 +21,8 @@ use {
     },
     std::{
         fmt::{ Display, Formatter, Result as FmtResult, },
-        fs::read_to_string,
+        fs::{ File, read_to_string, },
+        io::{ BufRead, BufReader, },
         path::{ Path, PathBuf, },
     },
 };
@@ -29,6 +30,20 @@ use {
 const SPACE: char = ' ';
 const TAB: char = '\t';
 const NEWLINE: char = '\n';
+/// The line opening and closing the front matter of a file.
+const FRONT_MATTER: &str = "---";
+
+/// Whether a file begins with front matter, reading only its first line.
+pub(crate)
+fn has_front_matter<P: AsRef<Path>>(p: P) -> bool {
+    let mut line = String::new();
+
+    match File::open(p) {
+        Ok(file) => BufReader::new(file).read_line(&mut line).is_ok() &&
+            line.trim_end() == FRONT_MATTER,
+        Err(_) => false,
+    }
+}
 
 pub(crate)
 fn read_file<P: AsRef<Path>>(p: P) -> Result<String> {
@@ -90,7 +105,7 @@ impl Coordinate {
 }
 
 /// A file that has been read into memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub
 struct Source {
     file: PathBuf,
@@ -154,6 +169,40 @@ impl Source {
         self.coord = coord;
     }
 
+    /// Take the front matter from the top of the source, the lines between
+    /// two `---` lines, leaving the source positioned after it.
+    pub(crate)
+    fn front_matter(&mut self) -> Option<String> {
+        if self.coord.line != 0 || self.coord.position != 0 ||
+            self.content.first().map(|l| l.trim_end()) != Some(FRONT_MATTER)
+        {
+            return None;
+        }
+
+        let end = self.content.iter()
+            .skip(1)
+            .position(|l| l.trim_end() == FRONT_MATTER)? + 1;
+
+        let matter = self.content[1..end].concat();
+
+        // take the closing line, which moves on to the next if there is one
+        self.coord = Coordinate { line: end, position: 0, };
+        self.take(self.content[end].len());
+
+        Some(matter)
+    }
+
+    /// The content from the current position to the end of the source.
+    pub(crate)
+    fn remaining(&self) -> String {
+        let mut output = self.pos().to_owned();
+        for line in self.content.iter().skip(self.coord.line + 1) {
+            output.push_str(line);
+        }
+
+        output
+    }
+
     fn eol(&self) -> bool {
         self.coord.position == self.content[self.coord.line].len()
     }
@@ -167,17 +216,19 @@ impl Source {
     fn skip_internal(&mut self) -> Option<char> {
         // still characters to read
         if !self.eof() {
-            let b = self.content[self.coord.line][self.coord.position..self.coord.position+1]
-                .as_bytes()[0];
+            let c = self.content[self.coord.line][self.coord.position..]
+                .chars()
+                .next()
+                .unwrap();
 
-            self.coord.position += 1;
+            self.coord.position += c.len_utf8();
             // if eol 
             if self.eol() && !self.eof() {
                 self.coord.position = 0;
                 self.coord.line += 1;
             }
 
-            Some(b as char)
+            Some(c)
         }
         // file ended
         else {
@@ -0,0 +1,99 @@
+//! Context file formats for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+mod csv;
+mod toml;
+mod yaml;
+
+use {
+    crate::{
+        context::JsonContext,
+        error::{
+            Error,
+            Result,
+        },
+    },
+    serde_json::Value as JsonValue,
+    std::path::Path,
+};
+
+/// An error while parsing a context file, as the line it occurred on and a
+/// message.
+type ParseError = (usize, String);
+
+/// The format of a context file.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate)
+enum Format {
+    #[default]
+    Json,
+    Toml,
+    Yaml,
+    Csv,
+}
+
+impl Format {
+    pub(crate)
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml"|"yml" => Some(Self::Yaml),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    /// The format of a file by its extension, json when it is unknown.
+    pub(crate)
+    fn from_path<P>(path: P) -> Self
+    where
+        P: AsRef<Path>
+    {
+        path.as_ref()
+            .extension()
+            .and_then(|ext| Self::from_name(&ext.to_string_lossy()))
+            .unwrap_or_default()
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Json => "Json",
+            Self::Toml => "Toml",
+            Self::Yaml => "Yaml",
+            Self::Csv => "Csv",
+        }
+    }
+
+    pub(crate)
+    fn parse<P, S>(self, path: P, source: S) -> Result<JsonValue>
+    where
+        P: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        let source = source.as_ref();
+        let parsed = match self {
+            Self::Json => return JsonContext::parse_json(path, source),
+            Self::Toml => toml::parse(source),
+            Self::Yaml => yaml::parse(source),
+            Self::Csv => csv::parse(source),
+        };
+
+        parsed.map_err(|(line, msg)| Error::SourceParse(
+            self.name().to_owned(), msg, line, path.as_ref().into(),
+        ))
+    }
+}
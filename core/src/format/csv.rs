@@ -0,0 +1,126 @@
+//! Csv context files for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use {
+    super::ParseError,
+    serde_json::{
+        Map as JsonMap,
+        Value as JsonValue,
+    },
+    std::result::Result as StdResult,
+};
+
+/// Split a document into records of fields, each with the line it starts
+/// on, skipping empty lines.
+fn records(source: &str) -> StdResult<Vec<(usize, Vec<String>)>, ParseError> {
+    // spreadsheets often begin an export with a byte order mark
+    let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+
+    let mut records = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 0;
+
+    while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut was_quoted = false;
+
+        loop {
+            match chars.next() {
+                None if quoted => return Err((start, "unterminated quoted field".to_owned())),
+                None => break,
+                Some('"') if quoted => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    }
+                    else {
+                        quoted = false;
+                    }
+                },
+                Some('"') if field.is_empty() && !was_quoted => {
+                    quoted = true;
+                    was_quoted = true;
+                },
+                Some(c) if quoted => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+
+                    field.push(c);
+                },
+                Some(',') => {
+                    fields.push(std::mem::take(&mut field));
+                    was_quoted = false;
+                },
+                Some('\r') if chars.peek() == Some(&'\n') => {},
+                Some('\n') => {
+                    line += 1;
+                    break;
+                },
+                Some(_) if was_quoted => {
+                    return Err((line, "unexpected character after quoted field".to_owned()));
+                },
+                Some(c) => field.push(c),
+            }
+        }
+
+        if fields.is_empty() && field.is_empty() && !was_quoted {
+            continue;
+        }
+
+        fields.push(field);
+        records.push((start, fields));
+    }
+
+    Ok(records)
+}
+
+/// Parse a document into an array of objects, one for each row, keyed by the
+/// fields of the header row. All values are strings.
+pub(super)
+fn parse(source: &str) -> StdResult<JsonValue, ParseError> {
+    let mut records = records(source)?.into_iter();
+
+    let header = match records.next() {
+        Some((_, header)) => header,
+        None => return Ok(JsonValue::Array(Vec::new())),
+    };
+
+    let mut rows = Vec::new();
+    for (line, fields) in records {
+        if fields.len() > header.len() {
+            return Err((line, format!(
+                "row has {} fields but the header has {}",
+                fields.len(),
+                header.len(),
+            )));
+        }
+
+        let mut row = JsonMap::new();
+        let mut fields = fields.into_iter();
+        for key in &header {
+            // short rows are padded with empty fields
+            row.insert(key.to_owned(), fields.next().unwrap_or_default().into());
+        }
+
+        rows.push(JsonValue::Object(row));
+    }
+
+    Ok(JsonValue::Array(rows))
+}
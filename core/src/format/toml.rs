@@ -0,0 +1,493 @@
+//! Toml context files for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use {
+    super::ParseError,
+    serde_json::{
+        Map as JsonMap,
+        Number as JsonNumber,
+        Value as JsonValue,
+    },
+    std::result::Result as StdResult,
+};
+
+type TomlResult<T> = StdResult<T, ParseError>;
+
+/// A parser over the characters of a toml document.
+struct Toml {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Toml {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+
+        if c == '\n' {
+            self.line += 1;
+        }
+
+        Some(c)
+    }
+
+    fn err<T, S>(&self, msg: S) -> TomlResult<T>
+    where
+        S: Into<String>
+    {
+        Err((self.line, msg.into()))
+    }
+
+    fn expect(&mut self, c: char) -> TomlResult<()> {
+        match self.next() {
+            Some(n) if n == c => Ok(()),
+            Some(n) => self.err(format!("expected '{c}' but found '{n}'")),
+            None => self.err(format!("expected '{c}' but found the end of the file")),
+        }
+    }
+
+    /// Skip spaces and tabs.
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(' '|'\t')) {
+            self.next();
+        }
+    }
+
+    /// Skip whitespace, comments and line breaks.
+    fn skip_all(&mut self) {
+        loop {
+            match self.peek() {
+                Some(' '|'\t'|'\r'|'\n') => {
+                    self.next();
+                },
+                Some('#') => self.skip_comment(),
+                _ => break,
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        while !matches!(self.peek(), None|Some('\n')) {
+            self.next();
+        }
+    }
+
+    /// Expect the end of a line, allowing a trailing comment.
+    fn end_of_line(&mut self) -> TomlResult<()> {
+        self.skip_ws();
+
+        if self.peek() == Some('#') {
+            self.skip_comment();
+        }
+
+        match self.peek() {
+            None => Ok(()),
+            Some('\n') => {
+                self.next();
+                Ok(())
+            },
+            Some('\r') if self.starts_with("\r\n") => {
+                self.next();
+                self.next();
+                Ok(())
+            },
+            Some(c) => self.err(format!("unexpected character '{c}'")),
+        }
+    }
+
+    fn key_segment(&mut self) -> TomlResult<String> {
+        match self.peek() {
+            Some('"') => self.basic_string(),
+            Some('\'') => self.literal_string(),
+            _ => {
+                let mut key = String::new();
+                while let Some(c @ ('A'..='Z'|'a'..='z'|'0'..='9'|'_'|'-')) = self.peek() {
+                    key.push(c);
+                    self.next();
+                }
+
+                if key.is_empty() {
+                    return self.err("expected a key");
+                }
+
+                Ok(key)
+            },
+        }
+    }
+
+    /// Read a key, which can be dotted.
+    fn key(&mut self) -> TomlResult<Vec<String>> {
+        let mut key = Vec::new();
+
+        loop {
+            self.skip_ws();
+            key.push(self.key_segment()?);
+            self.skip_ws();
+
+            if self.peek() != Some('.') {
+                break;
+            }
+
+            self.next();
+        }
+
+        Ok(key)
+    }
+
+    fn escape(&mut self) -> TomlResult<char> {
+        let c = match self.next() {
+            Some('b') => '\u{8}',
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('f') => '\u{c}',
+            Some('r') => '\r',
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some(u @ ('u'|'U')) => {
+                let len = if u == 'u' { 4 } else { 8 };
+                let hex = (0..len).filter_map(|_| self.next()).collect::<String>();
+
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(c) => c,
+                    None => return self.err(format!("invalid unicode escape \"{hex}\"")),
+                }
+            },
+            Some(c) => return self.err(format!("invalid escape '\\{c}'")),
+            None => return self.err("unterminated string"),
+        };
+
+        Ok(c)
+    }
+
+    fn basic_string(&mut self) -> TomlResult<String> {
+        if self.starts_with("\"\"\"") {
+            return self.multiline_basic_string();
+        }
+
+        self.expect('"')?;
+
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => s.push(self.escape()?),
+                Some('\n')|None => return self.err("unterminated string"),
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn multiline_basic_string(&mut self) -> TomlResult<String> {
+        self.pos += 3;
+
+        // a line break directly after the delimiter is trimmed
+        if self.starts_with("\r\n") {
+            self.next();
+        }
+
+        if self.peek() == Some('\n') {
+            self.next();
+        }
+
+        let mut s = String::new();
+        loop {
+            if self.starts_with("\"\"\"") && !self.starts_with("\"\"\"\"") {
+                self.pos += 3;
+                return Ok(s);
+            }
+
+            match self.next() {
+                // a backslash at the end of a line trims the whitespace after it
+                Some('\\') if matches!(self.peek(), Some(' '|'\t'|'\r'|'\n')) => {
+                    while matches!(self.peek(), Some(' '|'\t'|'\r'|'\n')) {
+                        self.next();
+                    }
+                },
+                Some('\\') => s.push(self.escape()?),
+                Some(c) => s.push(c),
+                None => return self.err("unterminated string"),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> TomlResult<String> {
+        if self.starts_with("'''") {
+            self.pos += 3;
+
+            if self.starts_with("\r\n") {
+                self.next();
+            }
+
+            if self.peek() == Some('\n') {
+                self.next();
+            }
+
+            let mut s = String::new();
+            loop {
+                if self.starts_with("'''") && !self.starts_with("''''") {
+                    self.pos += 3;
+                    return Ok(s);
+                }
+
+                match self.next() {
+                    Some(c) => s.push(c),
+                    None => return self.err("unterminated string"),
+                }
+            }
+        }
+
+        self.expect('\'')?;
+
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some('\'') => return Ok(s),
+                Some('\n')|None => return self.err("unterminated string"),
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> TomlResult<JsonValue> {
+        self.expect('[')?;
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_all();
+
+            if self.peek() == Some(']') {
+                self.next();
+                return Ok(JsonValue::Array(items));
+            }
+
+            items.push(self.value()?);
+            self.skip_all();
+
+            match self.next() {
+                Some(',') => {},
+                Some(']') => return Ok(JsonValue::Array(items)),
+                _ => return self.err("expected ',' or ']' in array"),
+            }
+        }
+    }
+
+    fn inline_table(&mut self) -> TomlResult<JsonValue> {
+        self.expect('{')?;
+
+        let mut table = JsonMap::new();
+        self.skip_ws();
+
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(JsonValue::Object(table));
+        }
+
+        loop {
+            let key = self.key()?;
+            self.expect('=')?;
+            self.skip_ws();
+            let value = self.value()?;
+            self.insert(&mut table, &key, value)?;
+            self.skip_ws();
+
+            match self.next() {
+                Some(',') => self.skip_ws(),
+                Some('}') => return Ok(JsonValue::Object(table)),
+                _ => return self.err("expected ',' or '}' in inline table"),
+            }
+        }
+    }
+
+    /// Read a number, boolean or date, the last of which is kept as a string.
+    fn scalar(&mut self) -> TomlResult<JsonValue> {
+        let mut token = String::new();
+        while let Some(c @ ('A'..='Z'|'a'..='z'|'0'..='9'|'_'|'-'|'+'|'.'|':')) = self.peek() {
+            token.push(c);
+            self.next();
+        }
+
+        // a date and time can be separated by a space
+        if is_date(&token) && self.peek() == Some(' ') &&
+            self.chars.get(self.pos + 1).is_some_and(|c| c.is_ascii_digit())
+        {
+            self.next();
+            token.push('T');
+            while let Some(c @ ('0'..='9'|'-'|'+'|'.'|':'|'Z'|'z')) = self.peek() {
+                token.push(c);
+                self.next();
+            }
+        }
+
+        match token.as_str() {
+            "" => return self.err("expected a value"),
+            "true" => return Ok(JsonValue::Bool(true)),
+            "false" => return Ok(JsonValue::Bool(false)),
+            _ => {},
+        }
+
+        if is_date(&token) || is_time(&token) {
+            return Ok(JsonValue::String(token));
+        }
+
+        let digits = token.replace('_', "");
+        let int = if let Some(hex) = digits.strip_prefix("0x") {
+            i64::from_str_radix(hex, 16).ok()
+        }
+        else if let Some(oct) = digits.strip_prefix("0o") {
+            i64::from_str_radix(oct, 8).ok()
+        }
+        else if let Some(bin) = digits.strip_prefix("0b") {
+            i64::from_str_radix(bin, 2).ok()
+        }
+        else {
+            digits.parse::<i64>().ok()
+        };
+
+        if let Some(int) = int {
+            return Ok(JsonValue::Number(int.into()));
+        }
+
+        match digits.parse::<f64>().ok().and_then(JsonNumber::from_f64) {
+            Some(n) if !digits.contains(['i', 'n']) => Ok(JsonValue::Number(n)),
+            _ => self.err(format!("invalid value \"{token}\"")),
+        }
+    }
+
+    fn value(&mut self) -> TomlResult<JsonValue> {
+        match self.peek() {
+            Some('"') => Ok(JsonValue::String(self.basic_string()?)),
+            Some('\'') => Ok(JsonValue::String(self.literal_string()?)),
+            Some('[') => self.array(),
+            Some('{') => self.inline_table(),
+            _ => self.scalar(),
+        }
+    }
+
+    /// Find the table at a key within another, creating any which are missing.
+    /// The last table of an array of tables is used.
+    fn table<'a>(&self, mut table: &'a mut JsonMap<String, JsonValue>, key: &[String]) -> TomlResult<&'a mut JsonMap<String, JsonValue>> {
+        for seg in key {
+            let value = table.entry(seg.to_owned())
+                .or_insert_with(|| JsonValue::Object(JsonMap::new()));
+
+            let value = match value {
+                JsonValue::Array(items) => match items.last_mut() {
+                    Some(last) => last,
+                    None => return self.err(format!("key \"{seg}\" is not a table")),
+                },
+                value => value,
+            };
+
+            table = match value {
+                JsonValue::Object(t) => t,
+                _ => return self.err(format!("key \"{seg}\" is not a table")),
+            };
+        }
+
+        Ok(table)
+    }
+
+    fn insert(&self, table: &mut JsonMap<String, JsonValue>, key: &[String], value: JsonValue) -> TomlResult<()> {
+        let (last, parents) = key.split_last().unwrap();
+        let table = self.table(table, parents)?;
+
+        if table.contains_key(last) {
+            return self.err(format!("duplicate key \"{}\"", key.join(".")));
+        }
+
+        table.insert(last.to_owned(), value);
+
+        Ok(())
+    }
+}
+
+fn is_date(token: &str) -> bool {
+    let b = token.as_bytes();
+    b.len() >= 10 && b[..4].iter().all(u8::is_ascii_digit) && b[4] == b'-' && b[7] == b'-'
+}
+
+fn is_time(token: &str) -> bool {
+    let b = token.as_bytes();
+    b.len() >= 8 && b[..2].iter().all(u8::is_ascii_digit) && b[2] == b':'
+}
+
+/// Parse a document into an object. Dates and times are kept as strings.
+pub(super)
+fn parse(source: &str) -> TomlResult<JsonValue> {
+    let mut p = Toml { chars: source.chars().collect(), pos: 0, line: 0, };
+    let mut root = JsonMap::new();
+    let mut current: Vec<String> = Vec::new();
+
+    loop {
+        p.skip_all();
+
+        if p.peek().is_none() {
+            break;
+        }
+
+        if p.peek() == Some('[') {
+            p.next();
+            let is_array = p.peek() == Some('[');
+            if is_array {
+                p.next();
+            }
+
+            let key = p.key()?;
+            p.expect(']')?;
+
+            if is_array {
+                p.expect(']')?;
+
+                let (last, parents) = key.split_last().unwrap();
+                let parent = p.table(&mut root, parents)?;
+                let entry = parent.entry(last.to_owned())
+                    .or_insert_with(|| JsonValue::Array(Vec::new()));
+
+                match entry {
+                    JsonValue::Array(tables) => tables.push(JsonValue::Object(JsonMap::new())),
+                    _ => return p.err(format!("key \"{}\" is not an array of tables", key.join("."))),
+                }
+            }
+            else {
+                p.table(&mut root, &key)?;
+            }
+
+            current = key;
+            p.end_of_line()?;
+            continue;
+        }
+
+        let key = p.key()?;
+        p.expect('=')?;
+        p.skip_ws();
+        let value = p.value()?;
+
+        let table = p.table(&mut root, &current)?;
+        p.insert(table, &key, value)?;
+        p.end_of_line()?;
+    }
+
+    Ok(JsonValue::Object(root))
+}
@@ -0,0 +1,542 @@
+//! Yaml context files for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use {
+    super::ParseError,
+    serde_json::{
+        Map as JsonMap,
+        Number as JsonNumber,
+        Value as JsonValue,
+    },
+    std::result::Result as StdResult,
+};
+
+type YamlResult<T> = StdResult<T, ParseError>;
+
+/// A line of a yaml document.
+#[derive(Clone, Debug)]
+struct Line {
+    number: usize,
+    indent: usize,
+    /// The line without its indentation or comment.
+    text: String,
+    /// The line as written, used for block scalars.
+    raw: String,
+}
+
+impl Line {
+    fn is_blank(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn is_item(&self) -> bool {
+        self.text == "-" || self.text.starts_with("- ")
+    }
+}
+
+/// Remove a comment from a line, ignoring any within quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut prev = ' ';
+
+    for (idx, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"'|'\'') if prev == ' ' || idx == 0 || matches!(prev, '['|'{'|',') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if prev == ' ' || prev == '\t' || idx == 0 => return &line[..idx],
+            _ => {},
+        }
+
+        prev = c;
+    }
+
+    line
+}
+
+/// Find the colon separating a mapping key from its value, ignoring any
+/// within quotes or flow collections.
+fn key_split(text: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut depth = 0;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+
+    for (i, &(idx, c)) in chars.iter().enumerate() {
+        match (quote, c) {
+            (None, '"'|'\'') if idx == 0 => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {},
+            (None, '['|'{') if idx == 0 => return None,
+            (None, '['|'{') => depth += 1,
+            (None, ']'|'}') => depth -= 1,
+            (None, ':') if depth == 0 &&
+                chars.get(i + 1).map_or(true, |(_, n)| *n == ' ' || *n == '\t') => return Some(idx),
+            _ => {},
+        }
+    }
+
+    None
+}
+
+fn unquote(s: &str, line: usize) -> YamlResult<String> {
+    if let Some(inner) = s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        return Ok(inner.replace("''", "'"));
+    }
+
+    if let Some(inner) = s.strip_prefix('"') {
+        let mut chars = inner.chars();
+        let mut out = String::new();
+
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => out.push(match chars.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('0') => '\0',
+                    Some('"') => '"',
+                    Some('/') => '/',
+                    Some('\\') => '\\',
+                    Some(u @ ('x'|'u'|'U')) => {
+                        let len = match u { 'x' => 2, 'u' => 4, _ => 8, };
+                        let hex = chars.by_ref().take(len).collect::<String>();
+
+                        match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                            Some(c) => c,
+                            None => return Err((line, format!("invalid unicode escape \"{hex}\""))),
+                        }
+                    },
+                    Some(c) => return Err((line, format!("invalid escape '\\{c}'"))),
+                    None => return Err((line, "unterminated string".to_owned())),
+                }),
+                Some(c) => out.push(c),
+                None => return Err((line, "unterminated string".to_owned())),
+            }
+        }
+
+        if !chars.as_str().trim().is_empty() {
+            return Err((line, "unexpected characters after string".to_owned()));
+        }
+
+        return Ok(out);
+    }
+
+    Ok(s.to_owned())
+}
+
+/// Resolve a plain scalar to null, a boolean, a number or a string.
+fn plain(s: &str) -> JsonValue {
+    match s {
+        ""|"~"|"null"|"Null"|"NULL" => return JsonValue::Null,
+        "true"|"True"|"TRUE" => return JsonValue::Bool(true),
+        "false"|"False"|"FALSE" => return JsonValue::Bool(false),
+        _ => {},
+    }
+
+    let is_numeric = s.trim_start_matches(['-', '+'])
+        .starts_with(|c: char| c.is_ascii_digit() || c == '.') &&
+        s.chars().all(|c| c.is_ascii_hexdigit() || matches!(c, '-'|'+'|'.'|'x'|'o'|'e'|'E'));
+
+    if is_numeric {
+        let int = if let Some(hex) = s.strip_prefix("0x") {
+            i64::from_str_radix(hex, 16).ok()
+        }
+        else if let Some(oct) = s.strip_prefix("0o") {
+            i64::from_str_radix(oct, 8).ok()
+        }
+        else {
+            s.parse::<i64>().ok()
+        };
+
+        if let Some(int) = int {
+            return JsonValue::Number(int.into());
+        }
+
+        if let Some(n) = s.parse::<f64>().ok().and_then(JsonNumber::from_f64) {
+            return JsonValue::Number(n);
+        }
+    }
+
+    JsonValue::String(s.to_owned())
+}
+
+/// A parser over a flow collection, such as `[ a, { b: c } ]`.
+struct Flow<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    line: &'a Line,
+}
+
+impl Flow<'_> {
+    fn err<T, S>(&self, msg: S) -> YamlResult<T>
+    where
+        S: Into<String>
+    {
+        Err((self.line.number, msg.into()))
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.chars.get(self.pos), Some(' '|'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn scalar(&mut self, in_map: bool) -> YamlResult<JsonValue> {
+        self.skip_ws();
+
+        let start = self.pos;
+        if let Some(q @ ('"'|'\'')) = self.chars.get(self.pos).copied() {
+            self.pos += 1;
+
+            loop {
+                match self.chars.get(self.pos) {
+                    Some('\\') if q == '"' => self.pos += 2,
+                    Some(c) if *c == q => {
+                        // a doubled single quote is an escaped quote
+                        if q == '\'' && self.chars.get(self.pos + 1) == Some(&'\'') {
+                            self.pos += 2;
+                            continue;
+                        }
+
+                        self.pos += 1;
+                        break;
+                    },
+                    Some(_) => self.pos += 1,
+                    None => return self.err("unterminated string"),
+                }
+            }
+
+            let s = self.chars[start..self.pos].iter().collect::<String>();
+            return Ok(JsonValue::String(unquote(&s, self.line.number)?));
+        }
+
+        while let Some(c) = self.chars.get(self.pos) {
+            if matches!(c, ','|']'|'}') || (in_map && *c == ':') {
+                break;
+            }
+
+            self.pos += 1;
+        }
+
+        let s = self.chars[start..self.pos].iter().collect::<String>();
+        Ok(plain(s.trim()))
+    }
+
+    fn value(&mut self, in_map: bool) -> YamlResult<JsonValue> {
+        self.skip_ws();
+
+        match self.chars.get(self.pos) {
+            Some('[') => self.sequence(),
+            Some('{') => self.mapping(),
+            _ => self.scalar(in_map),
+        }
+    }
+
+    fn sequence(&mut self) -> YamlResult<JsonValue> {
+        self.pos += 1;
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_ws();
+            if self.chars.get(self.pos) == Some(&']') {
+                self.pos += 1;
+                return Ok(JsonValue::Array(items));
+            }
+
+            items.push(self.value(false)?);
+            self.skip_ws();
+
+            match self.chars.get(self.pos) {
+                Some(',') => self.pos += 1,
+                Some(']') => {},
+                _ => return self.err("expected ',' or ']' in flow sequence"),
+            }
+        }
+    }
+
+    fn mapping(&mut self) -> YamlResult<JsonValue> {
+        self.pos += 1;
+        let mut map = JsonMap::new();
+
+        loop {
+            self.skip_ws();
+            if self.chars.get(self.pos) == Some(&'}') {
+                self.pos += 1;
+                return Ok(JsonValue::Object(map));
+            }
+
+            let key = match self.scalar(true)? {
+                JsonValue::String(s) => s,
+                v => v.to_string(),
+            };
+
+            self.skip_ws();
+            let value = if self.chars.get(self.pos) == Some(&':') {
+                self.pos += 1;
+                self.value(true)?
+            }
+            else {
+                JsonValue::Null
+            };
+
+            map.insert(key, value);
+            self.skip_ws();
+
+            match self.chars.get(self.pos) {
+                Some(',') => self.pos += 1,
+                Some('}') => {},
+                _ => return self.err("expected ',' or '}' in flow mapping"),
+            }
+        }
+    }
+}
+
+/// A parser over the lines of a yaml document.
+struct Yaml {
+    lines: Vec<Line>,
+    idx: usize,
+}
+
+impl Yaml {
+    fn skip_blank(&mut self) {
+        while self.lines.get(self.idx).is_some_and(|l| l.is_blank()) {
+            self.idx += 1;
+        }
+    }
+
+    fn current(&mut self) -> Option<Line> {
+        self.skip_blank();
+        self.lines.get(self.idx).cloned()
+    }
+
+    /// Parse the value written on the same line as a key or item marker.
+    fn inline(&mut self, text: &str, line: &Line, indent: usize) -> YamlResult<JsonValue> {
+        if text.starts_with('[') || text.starts_with('{') {
+            let mut flow = Flow { chars: text.chars().collect(), pos: 0, line, };
+            let value = flow.value(false)?;
+            flow.skip_ws();
+
+            if flow.pos != flow.chars.len() {
+                return flow.err("unexpected characters after flow collection");
+            }
+
+            return Ok(value);
+        }
+
+        if let Some(header) = text.strip_prefix('|').or(text.strip_prefix('>')) {
+            return self.block_scalar(text.starts_with('>'), header, indent);
+        }
+
+        if text.starts_with('"') || text.starts_with('\'') {
+            return Ok(JsonValue::String(unquote(text, line.number)?));
+        }
+
+        Ok(plain(text))
+    }
+
+    /// Read the lines of a literal or folded block scalar, which are indented
+    /// further than its parent.
+    fn block_scalar(&mut self, folded: bool, header: &str, indent: usize) -> YamlResult<JsonValue> {
+        let mut lines = Vec::new();
+        let mut content_indent = None;
+
+        while let Some(line) = self.lines.get(self.idx) {
+            if line.raw.trim().is_empty() {
+                lines.push(String::new());
+                self.idx += 1;
+                continue;
+            }
+
+            let line_indent = line.raw.len() - line.raw.trim_start_matches(' ').len();
+            if line_indent <= indent {
+                break;
+            }
+
+            let content_indent = *content_indent.get_or_insert(line_indent);
+            lines.push(line.raw.get(content_indent.min(line_indent)..).unwrap_or_default().to_owned());
+            self.idx += 1;
+        }
+
+        let trailing = lines.iter().rev().take_while(|l| l.is_empty()).count();
+        lines.truncate(lines.len() - trailing);
+
+        let mut out = if folded {
+            let mut out = String::new();
+            for (i, line) in lines.iter().enumerate() {
+                if i > 0 {
+                    out.push(if line.is_empty() || lines[i - 1].is_empty() { '\n' } else { ' ' });
+                }
+
+                out.push_str(line);
+            }
+            out
+        }
+        else {
+            lines.join("\n")
+        };
+
+        if !header.contains('-') && !lines.is_empty() {
+            out.push('\n');
+        }
+
+        if header.contains('+') {
+            out.push_str(&"\n".repeat(trailing));
+        }
+
+        Ok(JsonValue::String(out))
+    }
+
+    fn node(&mut self, indent: usize) -> YamlResult<JsonValue> {
+        let line = match self.current() {
+            Some(line) if line.indent >= indent => line,
+            _ => return Ok(JsonValue::Null),
+        };
+
+        if line.is_item() {
+            self.sequence(line.indent)
+        }
+        else if key_split(&line.text).is_some() {
+            self.mapping(line.indent)
+        }
+        else {
+            self.idx += 1;
+            self.inline(&line.text, &line, line.indent)
+        }
+    }
+
+    fn sequence(&mut self, indent: usize) -> YamlResult<JsonValue> {
+        let mut items = Vec::new();
+
+        while let Some(line) = self.current() {
+            // a sequence at the level of its key ends at the next key
+            if line.indent < indent || (line.indent == indent && !line.is_item()) {
+                break;
+            }
+            else if line.indent > indent {
+                return Err((line.number, "unexpected indentation in sequence".to_owned()));
+            }
+
+            let rest = line.text[1..].trim_start();
+            if rest.is_empty() {
+                self.idx += 1;
+                items.push(self.node(indent + 1)?);
+                continue;
+            }
+
+            if !rest.starts_with("- ") && rest != "-" && key_split(rest).is_none() {
+                self.idx += 1;
+                items.push(self.inline(rest, &line, indent)?);
+                continue;
+            }
+
+            // the rest of the line is parsed as though it began a line of its
+            // own, allowing mappings to continue on the lines below
+            let offset = line.text.len() - rest.len();
+            self.lines[self.idx] = Line {
+                indent: line.indent + offset,
+                text: rest.to_owned(),
+                ..line.clone()
+            };
+
+            items.push(self.node(line.indent + offset)?);
+        }
+
+        Ok(JsonValue::Array(items))
+    }
+
+    fn mapping(&mut self, indent: usize) -> YamlResult<JsonValue> {
+        let mut map = JsonMap::new();
+
+        while let Some(line) = self.current() {
+            if line.indent < indent {
+                break;
+            }
+            else if line.indent > indent {
+                return Err((line.number, "unexpected indentation in mapping".to_owned()));
+            }
+
+            let split = match key_split(&line.text) {
+                Some(split) => split,
+                None => return Err((line.number, "expected a mapping key".to_owned())),
+            };
+
+            let key = unquote(line.text[..split].trim(), line.number)?;
+            let rest = line.text[split + 1..].trim();
+            self.idx += 1;
+
+            let value = if !rest.is_empty() {
+                self.inline(rest, &line, indent)?
+            }
+            else {
+                match self.current() {
+                    Some(next) if next.indent > indent => self.node(next.indent)?,
+                    // a sequence can be indented at the same level as its key
+                    Some(next) if next.indent == indent && next.is_item() => self.sequence(indent)?,
+                    _ => JsonValue::Null,
+                }
+            };
+
+            if map.contains_key(&key) {
+                return Err((line.number, format!("duplicate key \"{key}\"")));
+            }
+
+            map.insert(key, value);
+        }
+
+        Ok(JsonValue::Object(map))
+    }
+}
+
+/// Parse a document written in the block style of yaml, with flow collections
+/// on a single line, quoted and plain scalars, and literal and folded block
+/// scalars. Anchors, tags and multiple documents are not supported.
+pub(super)
+fn parse(source: &str) -> YamlResult<JsonValue> {
+    let mut lines = Vec::new();
+
+    for (number, raw) in source.lines().enumerate() {
+        let raw = raw.trim_end_matches('\r');
+
+        // document markers are treated as blank lines
+        if raw == "---" || raw == "..." {
+            lines.push(Line { number, indent: 0, text: String::new(), raw: String::new(), });
+            continue;
+        }
+
+        if raw.starts_with('\t') {
+            return Err((number, "tabs cannot be used for indentation".to_owned()));
+        }
+
+        let text = strip_comment(raw).trim_end();
+        let trimmed = text.trim_start_matches(' ');
+
+        lines.push(Line {
+            number,
+            indent: text.len() - trimmed.len(),
+            text: trimmed.to_owned(),
+            raw: raw.to_owned(),
+        });
+    }
+
+    let mut yaml = Yaml { lines, idx: 0, };
+    let value = yaml.node(0)?;
+
+    if let Some(line) = yaml.current() {
+        return Err((line.number, "unexpected content after document".to_owned()));
+    }
+
+    Ok(value)
+}
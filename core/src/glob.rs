@@ -0,0 +1,132 @@
+//! Path patterns for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::{
+    Component,
+    Path,
+    PathBuf,
+};
+
+/// The segment matching any number of path components.
+const ANY_COMPONENTS: &str = "**";
+
+/// A pattern matched against relative paths, in which `*` matches any
+/// characters within a component, `?` matches a single character, and `**`
+/// matches any number of components.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate)
+struct Glob(Vec<String>);
+
+impl Glob {
+    pub(crate)
+    fn new<S>(pattern: S) -> Self
+    where
+        S: AsRef<str>
+    {
+        Self(pattern.as_ref()
+            .split('/')
+            .filter(|s| !s.is_empty() && *s != ".")
+            .map(|s| s.to_owned())
+            .collect())
+    }
+
+    /// Whether a path component holds a wildcard.
+    pub(crate)
+    fn is_pattern<S>(component: S) -> bool
+    where
+        S: AsRef<str>
+    {
+        component.as_ref().contains([ '*', '?', ])
+    }
+
+    /// Split a path into the directory before its first wildcard and the
+    /// pattern which follows, if any.
+    pub(crate)
+    fn split_path<P>(path: P) -> (PathBuf, Option<Self>)
+    where
+        P: AsRef<Path>
+    {
+        let mut root = PathBuf::new();
+        let mut pattern = Vec::new();
+
+        for comp in path.as_ref().components() {
+            let s = comp.as_os_str().to_string_lossy();
+            if !pattern.is_empty() || Self::is_pattern(&s) {
+                pattern.push(s.into_owned());
+            }
+            else {
+                root.push(comp);
+            }
+        }
+
+        if pattern.is_empty() {
+            (root, None)
+        }
+        else {
+            (root, Some(Self(pattern)))
+        }
+    }
+
+    /// The greatest number of components a matching path can have, `None`
+    /// when unbounded.
+    pub(crate)
+    fn depth(&self) -> Option<usize> {
+        if self.0.iter().any(|s| s == ANY_COMPONENTS) {
+            None
+        }
+        else {
+            Some(self.0.len())
+        }
+    }
+
+    pub(crate)
+    fn matches<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>
+    {
+        let comps = path.as_ref().components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => Some(s.to_str()),
+                _ => None,
+            })
+            .collect::<Option<Vec<&str>>>();
+
+        match comps {
+            Some(comps) => matches_components(&self.0, &comps),
+            None => false,
+        }
+    }
+}
+
+fn matches_components(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((seg, rest)) if seg == ANY_COMPONENTS => (0..=path.len())
+            .any(|i| matches_components(rest, &path[i..])),
+        Some((seg, rest)) => !path.is_empty() &&
+            matches_component(&seg.chars().collect::<Vec<char>>(), &path[0].chars().collect::<Vec<char>>()) &&
+            matches_components(rest, &path[1..]),
+    }
+}
+
+fn matches_component(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| matches_component(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && matches_component(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_component(rest, &name[1..]),
+    }
+}
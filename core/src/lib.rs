@@ -18,10 +18,18 @@
 mod test;
 
 pub(crate) mod context;
+pub(crate) mod date;
 pub mod error;
+pub(crate) mod escape;
 pub(crate) mod file;
+pub(crate) mod format;
+pub(crate) mod glob;
+pub(crate) mod number;
 pub(crate) mod path;
 pub(crate) mod parser;
+pub(crate) mod schema;
+pub(crate) mod sort;
+pub(crate) mod transform;
 
 pub use {
     error::{
@@ -29,5 +37,6 @@ pub use {
         Result,
     },
     context::JsonContext,
+    escape::Escape,
     parser::Parser,
 };
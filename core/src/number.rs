@@ -0,0 +1,294 @@
+//! Numeric formatting for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt::{
+    Display,
+    Formatter,
+    Result as FmtResult,
+};
+
+/// The largest exponent accepted when reading a number.
+const MAX_EXPONENT: i64 = 1024;
+
+/// The units used when formatting a number of bytes.
+const BYTE_UNITS: [&str; 7] = [ "B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB", ];
+
+/// How digits are discarded when a number is rounded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate)
+enum Rounding {
+    /// Ties are rounded away from zero.
+    HalfUp,
+    /// Ties are rounded to the nearest even digit.
+    HalfEven,
+    /// Rounded toward positive infinity.
+    Ceil,
+    /// Rounded toward negative infinity.
+    Floor,
+    /// Rounded toward zero.
+    Trunc,
+}
+
+impl Rounding {
+    /// Read a rounding mode from its name.
+    pub(crate)
+    fn from_name<S>(name: S) -> Option<Self>
+    where
+        S: AsRef<str>
+    {
+        match name.as_ref() {
+            "half-up" => Some(Self::HalfUp),
+            "half-even" => Some(Self::HalfEven),
+            "ceil" => Some(Self::Ceil),
+            "floor" => Some(Self::Floor),
+            "trunc" => Some(Self::Trunc),
+            _ => None,
+        }
+    }
+}
+
+/// A number held as its decimal digits so that rounding is exact.
+struct Decimal {
+    negative: bool,
+    int: String,
+    frac: String,
+}
+
+impl Decimal {
+    /// Read a number written in json notation, surrounding whitespace and a
+    /// leading plus sign being allowed.
+    fn parse<S>(value: S) -> Option<Self>
+    where
+        S: AsRef<str>
+    {
+        let value = value.as_ref().trim();
+
+        let (negative, value) = if let Some(v) = value.strip_prefix('-') {
+            (true, v)
+        }
+        else {
+            (false, value.strip_prefix('+').unwrap_or(value))
+        };
+
+        let (mantissa, exponent) = match value.split_once([ 'e', 'E', ]) {
+            Some((m, e)) => {
+                let e = e.strip_prefix('+').unwrap_or(e);
+                let digits = e.strip_prefix('-').unwrap_or(e);
+
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+
+                (m, e.parse::<i64>().ok().filter(|e| e.abs() <= MAX_EXPONENT)?)
+            },
+            None => (value, 0),
+        };
+
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+        if int.is_empty() && frac.is_empty() ||
+            !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let digits = format!("{int}{frac}");
+        let point = int.len() as i64 + exponent;
+
+        let (int, frac) = if exponent == 0 {
+            (int.to_owned(), frac.to_owned())
+        }
+        else if point <= 0 {
+            ("".to_owned(), format!("{}{digits}", "0".repeat(-point as usize)))
+        }
+        else if point as usize >= digits.len() {
+            (format!("{digits}{}", "0".repeat(point as usize - digits.len())), "".to_owned())
+        }
+        else {
+            let (i, f) = digits.split_at(point as usize);
+            (i.to_owned(), f.to_owned())
+        };
+
+        Some(Self::new(negative, int, frac))
+    }
+
+    fn new(negative: bool, int: String, frac: String) -> Self {
+        let int = match int.trim_start_matches('0') {
+            "" => "0".to_owned(),
+            i => i.to_owned(),
+        };
+
+        let negative = negative &&
+            int.bytes().chain(frac.bytes()).any(|b| b != b'0');
+
+        Self { negative, int, frac, }
+    }
+
+    /// Multiply by ten to the power of `places`.
+    fn shift(self, places: usize) -> Self {
+        let mut frac = self.frac;
+        if frac.len() < places {
+            frac.push_str(&"0".repeat(places - frac.len()));
+        }
+
+        let (moved, frac) = frac.split_at(places);
+        Self::new(self.negative, format!("{}{moved}", self.int), frac.to_owned())
+    }
+
+    /// Round to exactly `places` fractional digits.
+    fn round(self, places: usize, mode: Rounding) -> Self {
+        if self.frac.len() <= places {
+            let frac = format!("{:0<places$}", self.frac);
+            return Self::new(self.negative, self.int, frac);
+        }
+
+        let (kept, rest) = self.frac.split_at(places);
+        let mut digits = format!("{}{kept}", self.int).into_bytes();
+
+        let discarded = rest.bytes().any(|b| b != b'0');
+        let first = rest.as_bytes()[0];
+        let last_odd = digits.last().map(|d| (d - b'0') % 2 == 1).unwrap_or(false);
+
+        let up = match mode {
+            Rounding::Trunc => false,
+            Rounding::Floor => self.negative && discarded,
+            Rounding::Ceil => !self.negative && discarded,
+            Rounding::HalfUp => first >= b'5',
+            Rounding::HalfEven => first > b'5' || first == b'5' && (
+                rest.bytes().skip(1).any(|b| b != b'0') || last_odd
+            ),
+        };
+
+        if up {
+            let mut carry = true;
+            for d in digits.iter_mut().rev() {
+                if *d == b'9' {
+                    *d = b'0';
+                }
+                else {
+                    *d += 1;
+                    carry = false;
+                    break;
+                }
+            }
+
+            if carry {
+                digits.insert(0, b'1');
+            }
+        }
+
+        let digits = String::from_utf8(digits).unwrap();
+        let (int, frac) = digits.split_at(digits.len() - places);
+
+        Self::new(self.negative, int.to_owned(), frac.to_owned())
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, fmtr: &mut Formatter<'_>) -> FmtResult {
+        if self.negative {
+            fmtr.write_str("-")?;
+        }
+
+        fmtr.write_str(&self.int)?;
+
+        if !self.frac.is_empty() {
+            fmtr.write_fmt(format_args!(".{}", self.frac))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Round a number to exactly `places` decimal places.
+pub(crate)
+fn fixed<S>(value: S, places: usize, mode: Rounding) -> Option<String>
+where
+    S: AsRef<str>
+{
+    Some(Decimal::parse(value)?.round(places, mode).to_string())
+}
+
+/// Separate the thousands of a number with `sep`, writing its fractional part
+/// after `point`.
+pub(crate)
+fn thousands<S>(value: S, sep: &str, point: &str) -> Option<String>
+where
+    S: AsRef<str>
+{
+    let number = Decimal::parse(value)?;
+    let mut output = String::new();
+
+    if number.negative {
+        output.push('-');
+    }
+
+    let len = number.int.len();
+    for (i, c) in number.int.chars().enumerate() {
+        if i > 0 && (len - i) % 3 == 0 {
+            output.push_str(sep);
+        }
+
+        output.push(c);
+    }
+
+    if !number.frac.is_empty() {
+        output.push_str(point);
+        output.push_str(&number.frac);
+    }
+
+    Some(output)
+}
+
+/// Write a ratio as a percentage rounded to `places` decimal places.
+pub(crate)
+fn percent<S>(value: S, places: usize, mode: Rounding) -> Option<String>
+where
+    S: AsRef<str>
+{
+    Some(format!("{}%", Decimal::parse(value)?.shift(2).round(places, mode)))
+}
+
+/// Write a number of bytes in the largest binary unit which keeps it at or
+/// above one.
+pub(crate)
+fn bytes<S>(value: S) -> Option<String>
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let number = Decimal::parse(value)?;
+    let sign = if number.negative { "-" } else { "" };
+
+    let mut size = value.trim().parse::<f64>().ok()?.abs();
+    let mut unit = 0;
+
+    while unit < BYTE_UNITS.len() - 1 &&
+        (unit == 0 && size >= 1024.0 || unit > 0 && (size * 10.0).round() >= 10240.0)
+    {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        return Some(format!("{sign}{} {}", size.round(), BYTE_UNITS[unit]));
+    }
+
+    let size = format!("{:.1}", size);
+    let size = size.strip_suffix(".0").unwrap_or(&size);
+
+    Some(format!("{sign}{size} {}", BYTE_UNITS[unit]))
+}
@@ -20,26 +20,60 @@ use {
     crate::{
         context::{
             Alias,
+            ArrayMerge,
             JsonContext,
+            Merge,
+            NullMerge,
+        },
+        date::{
+            self,
+            DateFormat,
+            DateTime,
         },
         error::{
             Error,
             Result,
         },
+        escape::Escape,
         file::{
             Coordinate,
             Source,
+            has_front_matter,
             read_file,
         },
+        format::Format,
+        glob::Glob,
+        number::{
+            self,
+            Rounding,
+        },
+        schema::Schema,
+        sort::{
+            self,
+            SortOrder,
+        },
+        transform,
     },
     nfm_core::Parser as NfmParser,
-    serde_json::Value as JsonValue,
+    serde_json::{
+        json,
+        Value as JsonValue,
+        Map as JsonMap,
+    },
     std::{
+        cell::OnceCell,
+        collections::HashMap,
         env::current_dir,
+        io::Result as IOResult,
         path::{
             Path,
             PathBuf,
         },
+        rc::Rc,
+        time::{
+            SystemTime,
+            UNIX_EPOCH,
+        },
     },
 };
 
@@ -53,6 +87,123 @@ enum IncludeContentMod {
     Split(usize, usize),
     Trim,
     Json,
+    Raw,
+    Escape(Escape),
+    Truncate(usize, String),
+    Capitalize,
+    Title,
+    Slugify,
+    PadLeft(usize, String),
+    PadRight(usize, String),
+    Substring(usize, usize),
+    Repeat(usize),
+    StripPrefix(String),
+    StripSuffix(String),
+    WordCount,
+    EscapeNewlines,
+    Fixed(usize, Rounding),
+    Thousands(String, String),
+    Percent(usize, Rounding),
+    Bytes,
+    Date(DateFormat),
+    Join(String),
+    Length,
+    First,
+    Last,
+    Nth(usize),
+    Slice(usize, usize),
+    Reverse,
+}
+
+impl IncludeContentMod {
+    /// Whether the modifier operates on json values rather than strings.
+    fn is_array_mod(&self) -> bool {
+        matches!(
+            self,
+            Self::Join(_)|Self::Length|Self::First|Self::Last|Self::Nth(_)|
+            Self::Slice(_, _)|Self::Reverse
+        )
+    }
+}
+
+/// How a loop continues after a break or continue tag.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LoopControl {
+    Break,
+    Continue,
+}
+
+impl LoopControl {
+    fn tag_name(self) -> &'static str {
+        match self {
+            Self::Break => consts::exp::BREAK,
+            Self::Continue => consts::exp::CONTINUE,
+        }
+    }
+}
+
+/// The value given to a parameter of a macro.
+#[derive(Clone, Debug)]
+enum MacroArg {
+    Literal(String),
+    Alias(String),
+    Null,
+}
+
+/// A parameter of a macro, which is required when it has no default.
+#[derive(Clone, Debug)]
+struct MacroParam {
+    name: String,
+    default: Option<MacroArg>,
+}
+
+/// A template defined with the macro tag.
+#[derive(Clone, Debug)]
+struct Macro {
+    params: Vec<MacroParam>,
+    /// The source of the defining file, positioned at the start of the body.
+    source: Source,
+}
+
+/// The type of a parameter declared by a template.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ParamType {
+    String,
+    Number,
+    Bool,
+    Array,
+    Object,
+    Path,
+}
+
+impl ParamType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            consts::exp::TYPE_STRING => Some(Self::String),
+            consts::exp::TYPE_NUMBER => Some(Self::Number),
+            consts::exp::TYPE_BOOL => Some(Self::Bool),
+            consts::exp::TYPE_ARRAY => Some(Self::Array),
+            consts::exp::TYPE_OBJECT => Some(Self::Object),
+            consts::exp::TYPE_PATH => Some(Self::Path),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::String => consts::exp::TYPE_STRING,
+            Self::Number => consts::exp::TYPE_NUMBER,
+            Self::Bool => consts::exp::TYPE_BOOL,
+            Self::Array => consts::exp::TYPE_ARRAY,
+            Self::Object => consts::exp::TYPE_OBJECT,
+            Self::Path => consts::exp::TYPE_PATH,
+        }
+    }
+
+    /// Whether a quoted default is read as json rather than a string.
+    fn is_json(self) -> bool {
+        !matches!(self, Self::String|Self::Path)
+    }
 }
 
 #[derive(PartialEq)]
@@ -61,18 +212,102 @@ enum IncludeFileMod {
     Raw,
 }
 
+/// Skip, take, or group the items of a loop.
+#[derive(Clone, Copy)]
+enum LoopSlice {
+    Limit(usize),
+    Offset(usize),
+    Batch(usize),
+}
+
+impl LoopSlice {
+    fn apply(self, items: Vec<JsonValue>) -> Vec<JsonValue> {
+        match self {
+            Self::Limit(n) => items.into_iter().take(n).collect(),
+            Self::Offset(n) => items.into_iter().skip(n).collect(),
+            Self::Batch(n) => items.chunks(n)
+                .map(|batch| JsonValue::Array(batch.to_vec()))
+                .collect(),
+        }
+    }
+}
+
+/// Group the items of a loop by the value at a key.
+#[derive(Clone)]
+struct GroupBy {
+    key: String,
+    format: Option<DateFormat>,
+}
+
+impl GroupBy {
+    /// Gather items sharing a key, in the order each key is first seen. The
+    /// items of object entries are grouped by a key of their value.
+    fn apply(&self, items: Vec<JsonValue>, entries: bool) -> Result<Vec<JsonValue>> {
+        let (path, items_key) = if entries {
+            (format!("value.{}", self.key), "value")
+        }
+        else {
+            (self.key.clone(), "items")
+        };
+
+        let mut groups: Vec<(JsonValue, Vec<JsonValue>)> = Vec::new();
+        for item in items {
+            let mut key = sort::lookup(&item, &path).clone();
+
+            // dates are grouped by their formatted value, such as their year
+            if let (Some(format), false) = (&self.format, key.is_null()) {
+                key = key.as_str()
+                    .and_then(|k| date::format(k, format))
+                    .ok_or(Error::ValueNotDate(self.key.as_str().into()))?
+                    .into();
+            }
+
+            let value = if entries {
+                item["value"].clone()
+            }
+            else {
+                item
+            };
+
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.push(value),
+                None => groups.push((key, vec![ value, ])),
+            }
+        }
+
+        Ok(groups.into_iter()
+            .map(|(key, items)| {
+                let mut group = JsonMap::new();
+                group.insert("key".to_owned(), key);
+                group.insert(items_key.to_owned(), JsonValue::Array(items));
+                JsonValue::Object(group)
+            })
+            .collect())
+    }
+}
+
 #[derive(Clone)]
 enum ForFileMod {
     Ext(String),
     Reverse,
     Files,
     Dirs,
+    Slice(LoopSlice),
+    /// Descend into directories, to a maximum depth if given.
+    Recursive(Option<usize>),
+    Exclude(Glob),
+    Sort(SortOrder),
 }
 
 #[derive(Clone)]
 enum ForItemMod {
     Reverse,
     Paths,
+    Slice(LoopSlice),
+    Sort(SortOrder),
+    /// The coordinate of the condition items must meet.
+    Where(Coordinate),
+    Group(GroupBy),
 }
 
 #[derive(Clone)]
@@ -88,14 +323,112 @@ enum IfCondition {
     Truthy
 }
 
-#[derive(Default)]
 struct LoopFile {
     path: PathBuf,
+    relative: PathBuf,
     is_dir: bool,
     is_file: bool,
     ext: Option<String>,
     stem: Option<String>,
     name: Option<String>,
+    size: Option<u64>,
+    modified: Option<String>,
+    created: Option<String>,
+    front_matter: Option<JsonValue>,
+}
+
+impl LoopFile {
+    fn new(path: PathBuf, relative: PathBuf) -> Self {
+        let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_owned());
+        let stem = path.file_stem().and_then(|f| f.to_str())
+            .map(|f| f.to_owned());
+        let name = path.file_name().and_then(|f| f.to_str())
+            .map(|f| f.to_owned());
+
+        // metadata is left unset where the platform doesn't provide it
+        let meta = path.metadata().ok();
+        let size = meta.as_ref().filter(|m| m.is_file()).map(|m| m.len());
+        let modified = meta.as_ref().and_then(|m| Self::timestamp(m.modified()));
+        let created = meta.as_ref().and_then(|m| Self::timestamp(m.created()));
+
+        Self {
+            is_file: path.is_file(),
+            is_dir: path.is_dir(),
+            path,
+            relative,
+            ext,
+            stem,
+            name,
+            size,
+            modified,
+            created,
+            front_matter: None,
+        }
+    }
+
+    /// Read the front matter of the file without parsing the rest of it.
+    fn read_front_matter(&mut self) -> Result<()> {
+        if !self.is_file || !has_front_matter(&self.path) {
+            return Ok(());
+        }
+
+        let mut source = Source::read_file(&self.path)?;
+        self.front_matter = JsonContext::read_front_matter(&mut source)?
+            .map(JsonContext::into_value);
+
+        Ok(())
+    }
+
+    fn timestamp(time: IOResult<SystemTime>) -> Option<String> {
+        let seconds = time.ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        Some(DateTime::from_unix(seconds as i64).to_rfc3339())
+    }
+
+    /// The values of the file placed into the loop context.
+    fn entry(self) -> JsonValue {
+        json!({
+            "path": self.path.to_str().unwrap_or(""),
+            "relative": self.relative.to_str().unwrap_or(""),
+            "ext": self.ext,
+            "stem": self.stem,
+            "name": self.name,
+            "is_file": self.is_file,
+            "is_dir": self.is_dir,
+            "size": self.size,
+            "modified": self.modified,
+            "created": self.created,
+            "front_matter": self.front_matter,
+        })
+    }
+}
+
+/// Read the paths within `dir` along with their path relative to `root`,
+/// descending into directories until `max_depth` levels below `root`, and never
+/// into those which are excluded.
+fn walk_dir(
+    root: &Path, dir: &Path, depth: usize, max_depth: Option<usize>,
+    exclude: &[Glob], found: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<()> {
+    for entry in dir.read_dir().map_err(|e| Error::IO(e, dir.to_owned()))? {
+        let entry = entry.map_err(|e| Error::IO(e, dir.to_owned()))?;
+        let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_owned();
+
+        if exclude.iter().any(|g| g.matches(&relative)) {
+            continue;
+        }
+
+        // symbolic links to directories are not followed so that cycles can't
+        // occur
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if is_dir && max_depth.map_or(true, |max| depth < max) {
+            walk_dir(root, &path, depth + 1, max_depth, exclude, found)?;
+        }
+
+        found.push((path, relative));
+    }
+
+    Ok(())
 }
 
 /// The parser for Arcana templates.
@@ -104,25 +437,59 @@ pub
 struct Parser {
     path: PathBuf,
     context: Option<JsonContext>,
-    extends: Option<PathBuf>,
+    /// The template to extend and the coordinate of the tag extending it.
+    extends: Option<(PathBuf, Coordinate)>,
     can_extend: bool,
     source: Source,
     output: String,
+    escape: Option<Escape>,
+    /// The values exposed at the virtual `$build` alias, shared with every
+    /// parser spawned from this one.
+    build: Rc<OnceCell<JsonValue>>,
+    /// Set by a break or continue tag, skipping the rest of the iteration.
+    loop_control: Option<(LoopControl, Coordinate)>,
+    /// Shared with spawned parsers, and copied only when one defines a macro.
+    macros: Rc<HashMap<String, Macro>>,
+    /// The number of macro calls this parser is nested within.
+    macro_depth: usize,
+    /// The overrides of each named block, from the most derived template to
+    /// the least, positioned at the start of their bodies.
+    blocks: Rc<HashMap<String, Vec<Source>>>,
+    /// The output of the block being overridden.
+    super_block: Option<Rc<str>>,
+    /// The schema validating sourced contexts which don't name their own,
+    /// shared with spawned parsers.
+    context_schema: Option<Rc<Schema>>,
+    /// The files importing this one, which is then parsed only for the macros
+    /// it defines.
+    imports: Vec<PathBuf>,
 }
 
 impl Parser {
-    fn new_internal<P>(path: P, content: Option<String>, ctx: Option<JsonContext>) -> Result<Self>
+    fn new_internal<P>(
+        path: P, content: Option<String>, ctx: Option<JsonContext>,
+        escape: Option<Escape>,
+    ) -> Result<Self>
     where
         P: AsRef<Path>,
     {
         let abs_path = Self::normalize_initial_path(path)?;
-        let source = if let Some(c) = content {
+        let mut source = if let Some(c) = content {
             Source::faux_source(&abs_path, c)
         }
         else {
             Source::read_file(&abs_path)?
         };
 
+        // front matter is placed into the context before the body is parsed
+        let mut ctx = ctx;
+        if let Some(matter) = JsonContext::read_front_matter(&mut source)? {
+            match &mut ctx {
+                Some(ctx) => ctx.merge(&abs_path, matter)?,
+                None => ctx = Some(matter),
+            }
+        }
+
         Ok(Self {
             path: abs_path,
             context: ctx,
@@ -130,6 +497,15 @@ impl Parser {
             can_extend: true,
             source,
             output: String::new(),
+            escape,
+            build: Rc::default(),
+            loop_control: None,
+            macros: Rc::default(),
+            macro_depth: 0,
+            blocks: Rc::default(),
+            super_block: None,
+            context_schema: None,
+            imports: Vec::new(),
         })
     }
 
@@ -141,7 +517,21 @@ impl Parser {
         // take context from this parser
         let ctx = std::mem::take(&mut self.context);
         // initialize new parser at path with context and parse
-        let mut scoped_parser = Self::new_internal(p, None, ctx)?;
+        let mut scoped_parser = Self::new_internal(p, None, None, self.escape)?;
+        // the front matter of the extended template doesn't overwrite the
+        // values of the template extending it
+        scoped_parser.context = match (ctx, scoped_parser.context.take()) {
+            (Some(mut ctx), Some(matter)) => {
+                ctx.merge_missing(&scoped_parser.path, matter)?;
+                Some(ctx)
+            },
+            (ctx, matter) => ctx.or(matter),
+        };
+        scoped_parser.build = self.build.clone();
+        scoped_parser.macros = self.macros.clone();
+        scoped_parser.macro_depth = self.macro_depth;
+        scoped_parser.blocks = self.blocks.clone();
+        scoped_parser.context_schema = self.context_schema.clone();
         f(&mut scoped_parser)?;
         // deconstruct new parser into context and output
         let Parser { mut context, output, .. } = scoped_parser;
@@ -159,7 +549,12 @@ impl Parser {
         // clone context from this parser
         let new_ctx = self.context.clone();
         // initialize new parser with cloned context and parse
-        let mut scoped_parser = Self::new_internal(p, None, new_ctx)?;
+        let mut scoped_parser = Self::new_internal(p, None, new_ctx, self.escape)?;
+        scoped_parser.build = self.build.clone();
+        scoped_parser.macros = self.macros.clone();
+        scoped_parser.macro_depth = self.macro_depth;
+        scoped_parser.blocks = self.blocks.clone();
+        scoped_parser.context_schema = self.context_schema.clone();
         f(&mut scoped_parser)?;
         // deconstruct new parser into output
         let Parser { output, .. } = scoped_parser;
@@ -167,7 +562,7 @@ impl Parser {
         Ok(output)
     }
 
-    fn spawn_sealed_internal_parser<F>(&mut self, f: F) -> Result<String>
+    fn spawn_internal_parser<F>(&mut self, escape: Option<Escape>, f: F) -> Result<String>
     where
         F: FnOnce(&mut Parser) -> Result<()>
     {
@@ -178,6 +573,15 @@ impl Parser {
             can_extend: false,
             source: Source::default(),
             output: String::new(),
+            escape,
+            build: self.build.clone(),
+            loop_control: None,
+            macros: self.macros.clone(),
+            macro_depth: self.macro_depth,
+            blocks: self.blocks.clone(),
+            super_block: self.super_block.clone(),
+            context_schema: self.context_schema.clone(),
+            imports: Vec::new(),
         };
 
         // swap in the existing source
@@ -186,11 +590,35 @@ impl Parser {
         // swap the source back
         std::mem::swap(&mut self.source, &mut internal_parser.source);
         // deconstruct internal parser into output
-        let Parser { output, .. } = internal_parser;
+        let Parser { output, loop_control, macros, blocks, .. } = internal_parser;
+        // a break or continue within a block skips the rest of this one too
+        if loop_control.is_some() {
+            self.loop_control = loop_control;
+        }
+        // macros defined or imported and blocks overridden within a block,
+        // such as conditionally, are kept
+        self.macros = macros;
+        self.blocks = blocks;
         // return the output of the internal parser
         Ok(output)
     }
 
+    fn spawn_sealed_internal_parser<F>(&mut self, f: F) -> Result<String>
+    where
+        F: FnOnce(&mut Parser) -> Result<()>
+    {
+        self.spawn_internal_parser(self.escape, f)
+    }
+
+    fn spawn_unescaped_internal_parser<F>(&mut self, f: F) -> Result<String>
+    where
+        F: FnOnce(&mut Parser) -> Result<()>
+    {
+        // output is consumed as data rather than content, so it must never be
+        // escaped
+        self.spawn_internal_parser(Some(Escape::None), f)
+    }
+
     pub(crate)
     fn file(&self) -> &PathBuf {
         &self.path
@@ -213,6 +641,11 @@ impl Parser {
         &mut self.context
     }
 
+    pub(crate)
+    fn escape_mode(&self) -> Escape {
+        self.escape.unwrap_or_else(|| Escape::from_path(&self.path))
+    }
+
     pub(crate)
     fn src(&self) -> &Source {
         &self.source
@@ -258,7 +691,7 @@ impl Parser {
         Self::normalize_path_internal(self.directory(), p)
     }
 
-    fn read_ctx_in_internal<P, A>(&mut self, path: P, alias: Option<A>) -> Result<()>
+    fn read_ctx_in_internal<P, A>(&mut self, path: P, alias: Option<A>, format: Option<Format>, schema: Option<&Schema>) -> Result<()>
     where
         P: AsRef<Path>,
         A: Into<Alias>
@@ -267,37 +700,56 @@ impl Parser {
 
         if let Some(alias) = alias {
             if let Some(context) = &mut self.context {
-                context.read_in_as(path, alias)?;
+                context.read_in_as(path, alias, format, schema)?;
             }
             else {
-                self.context = Some(JsonContext::read_as(path, alias)?);
+                self.context = Some(JsonContext::read_as(path, alias, format, schema)?);
             }
         }
         else if let Some(context) = &mut self.context {
-            context.read_in(path)?;
+            context.read_in(path, format, schema)?;
         }
         else {
-            self.context = Some(JsonContext::read(path)?);
+            self.context = Some(JsonContext::read_format(path, format, schema)?);
         }
 
         Ok(())
     }
 
     pub(crate)
-    fn read_ctx_in<P>(&mut self, path: P) -> Result<()>
+    fn read_ctx_in<P>(&mut self, path: P, format: Option<Format>, schema: Option<&Schema>) -> Result<()>
+    where
+        P: AsRef<Path>
+    {
+        self.read_ctx_in_internal::<P, Alias>(path, None, format, schema)
+    }
+
+    fn read_ctx_in_merged<P>(
+        &mut self, path: P, alias: Option<String>, format: Option<Format>, schema: Option<&Schema>,
+        merge: &Merge,
+    ) -> Result<()>
     where
         P: AsRef<Path>
     {
-        self.read_ctx_in_internal::<P, Alias>(path, None)
+        let path = self.normalize_path(path);
+
+        // merging into nothing merges into an empty context, so that nulls
+        // are treated the same way
+        let context = match &mut self.context {
+            Some(context) => context,
+            None => self.context.insert(JsonContext::faux_context(self.file())?),
+        };
+
+        context.read_in_merged(path, alias, format, schema, merge)
     }
 
     pub(crate)
-    fn read_ctx_in_as<P, A>(&mut self, path: P, alias: A) -> Result<()>
+    fn read_ctx_in_as<P, A>(&mut self, path: P, alias: A, format: Option<Format>, schema: Option<&Schema>) -> Result<()>
     where
         P: AsRef<Path>,
         A: Into<Alias>
     {
-        self.read_ctx_in_internal(path, Some(alias))
+        self.read_ctx_in_internal(path, Some(alias), format, schema)
     }
 
     /// Create a new parser.
@@ -318,7 +770,7 @@ impl Parser {
     where
         P: AsRef<Path>
     {
-        Self::new_internal(path, None, None)
+        Self::new_internal(path, None, None, None)
     }
 
     /// Create a new parser with a specific context.
@@ -344,7 +796,7 @@ impl Parser {
     where
         T: AsRef<Path>
     {
-        Self::new_internal(template, None, Some(context))
+        Self::new_internal(template, None, Some(context), None)
     }
 
     /// Create a new parser with a specific context read from path.
@@ -371,7 +823,7 @@ impl Parser {
         C: AsRef<Path>
     {
         let ctx = JsonContext::read(context)?;
-        Self::new_internal(template, None, Some(ctx))
+        Self::new_internal(template, None, Some(ctx), None)
     }
 
     /// Create a new parser with an input string, pseudo-path, and a specific context.
@@ -402,7 +854,7 @@ impl Parser {
     where
         T: AsRef<Path>,
     {
-        Self::new_internal(template, Some(content), Some(context))
+        Self::new_internal(template, Some(content), Some(context), None)
     }
 
     /// Create a new parser with an input string and pseudo-path.
@@ -435,7 +887,97 @@ impl Parser {
     where
         T: AsRef<Path>,
     {
-        Self::new_internal(template, Some(content), None)
+        Self::new_internal(template, Some(content), None, None)
+    }
+
+    /// Set the escaping of included content, overriding the default which is
+    /// determined by each template's extension. The escaping also applies to
+    /// any template included or extended by the parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `escape` - The escaping mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arcana_core::{ Escape, Parser };
+    ///
+    /// let mut p = Parser::from_string_and_path(
+    ///     "./fake.path",
+    ///     concat!(
+    ///         "={title}(\"<b>Title</b>\")\\\n",
+    ///         "${title} ${title|raw}",
+    ///     ).to_owned(),
+    /// ).unwrap();
+    /// p.set_escape(Escape::Html);
+    /// p.parse().unwrap();
+    /// assert_eq!("&lt;b&gt;Title&lt;/b&gt; <b>Title</b>", p.as_output());
+    /// ```
+    pub
+    fn set_escape(&mut self, escape: Escape) {
+        self.escape = Some(escape);
+    }
+
+    /// Set the date exposed to templates as `$build.now`, overriding the
+    /// default which is read from `SOURCE_DATE_EPOCH` or the system clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - An RFC 3339 timestamp or date.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arcana_core::Parser;
+    ///
+    /// let mut p = Parser::from_string_and_path(
+    ///     "./fake.path",
+    ///     "${$build.now|date \"%B %-d, %Y\"}".to_owned(),
+    /// ).unwrap();
+    /// p.set_now("2026-03-03T09:30:00Z").unwrap();
+    /// p.parse().unwrap();
+    /// assert_eq!("March 3, 2026", p.as_output());
+    /// ```
+    pub
+    fn set_now<S>(&mut self, now: S) -> Result<()>
+    where
+        S: AsRef<str>
+    {
+        let now = now.as_ref();
+        if DateTime::parse(now).is_none() {
+            return Err(Error::IllegalDate(now.to_owned()));
+        }
+
+        self.build = Rc::new(OnceCell::from(json!({ "now": now })));
+
+        Ok(())
+    }
+
+    /// Set a JSON Schema to validate each context sourced by the template
+    /// against, unless the source tag names its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The path to the schema.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arcana_core::Parser;
+    ///
+    /// let mut p = Parser::new("test/schema/1/page.arcana").unwrap();
+    /// p.set_context_schema("test/schema/1/schema.json").unwrap();
+    /// p.parse().unwrap();
+    /// ```
+    pub
+    fn set_context_schema<P>(&mut self, schema: P) -> Result<()>
+    where
+        P: AsRef<Path>
+    {
+        let schema = Self::normalize_initial_path(schema)?;
+        self.context_schema = Some(Rc::new(Schema::read(schema)?));
+        Ok(())
     }
 
     fn esc_endblock(&mut self) {
@@ -450,7 +992,7 @@ impl Parser {
     {
         Error::IllegalCharacter(
             tag_name.as_ref().to_owned(),
-            self.src().pos()[0..1].chars().next().unwrap(),
+            self.src().pos().chars().next().unwrap(),
             self.src().coord(),
             self.src().file().to_owned(),
         )
@@ -491,7 +1033,7 @@ impl Parser {
         }
 
         return Err(Error::IllegalCharacterAfterExtends(
-            self.src().pos()[0..1].chars().next().unwrap(),
+            self.src().pos().chars().next().unwrap(),
             self.src().coord(),
             self.src().file().to_owned(),
         ));
@@ -510,24 +1052,23 @@ impl Parser {
 
     fn starts_with_alias_char(&self) -> bool {
         matches!(
-            &self.src().pos()[0..1],
-            "a"|"b"|"c"|"d"|"e"|"f"|"g"|"h"|"i"|"j"|"k"|"l"|"m"|"n"|
-            "o"|"p"|"q"|"r"|"s"|"t"|"u"|"v"|"w"|"x"|"y"|"z"|"A"|"B"|
-            "C"|"D"|"E"|"F"|"G"|"H"|"I"|"J"|"K"|"L"|"M"|"N"|"O"|"P"|
-            "Q"|"R"|"S"|"T"|"U"|"V"|"W"|"X"|"Y"|"Z"|"1"|"2"|"3"|"4"|
-            "5"|"6"|"7"|"8"|"9"|"0"|"_"|"-"|"."|"$"
+            self.src().pos().chars().next(),
+            Some('a'..='z'|'A'..='Z'|'0'..='9'|'_'|'-'|'.'|'$')
         )
     }
 
-    fn has_context(&self) -> Result<()> {
-        if self.context.is_some() {
-            return Ok(());
-        }
-
-        return Err(Error::ContextEmpty(
+    fn context_empty(&self) -> Error {
+        Error::ContextEmpty(
             self.src().coord(),
             self.src().file().to_owned()
-        ));
+        )
+    }
+
+    /// The values exposed at the virtual `$build` alias, computed the first
+    /// time they are needed by this parser or any it has spawned.
+    fn build_values(&self) -> Rc<OnceCell<JsonValue>> {
+        self.build.get_or_init(|| json!({ "now": DateTime::build_now().to_rfc3339() }));
+        self.build.clone()
     }
 
     pub(crate)
@@ -535,10 +1076,17 @@ impl Parser {
     where
         F: FnOnce(&mut JsonContext) -> Result<T>
     {
-        self.has_context()?;
+        let build = self.build_values();
 
-        let ctx = self.context.as_mut().unwrap();
-        f(ctx)
+        if let Some(ctx) = self.context.as_mut() {
+            ctx.set_build(build);
+            return f(ctx);
+        }
+
+        // without a context only the virtual aliases can be read
+        let mut ctx = JsonContext::faux_context(self.file())?;
+        ctx.set_build(build);
+        f(&mut ctx).map_err(|_| self.context_empty())
     }
 
     pub(crate)
@@ -546,12 +1094,17 @@ impl Parser {
     where
         F: FnOnce(&mut JsonContext) -> Result<Option<T>>
     {
-        if self.context.is_none() {
-            return Ok(None);
+        let build = self.build_values();
+
+        if let Some(ctx) = self.context.as_mut() {
+            ctx.set_build(build);
+            return f(ctx);
         }
 
-        let ctx = self.context.as_mut().unwrap();
-        f(ctx)
+        // without a context only the virtual aliases can be read
+        let mut ctx = JsonContext::faux_context(self.file())?;
+        ctx.set_build(build);
+        Ok(f(&mut ctx).unwrap_or(None))
     }
 
     fn path(&mut self, bypass: bool) -> Result<String> {
@@ -559,7 +1112,7 @@ impl Parser {
 
         self.src_mut().take(consts::PATH.len()).unwrap();
 
-        let output = self.spawn_sealed_internal_parser(|p| {
+        let output = self.spawn_unescaped_internal_parser(|p| {
             while !p.src().eof() && !p.src().pos().starts_with(consts::PATH) {
                 // if an escaped quote is encountered
                 if p.src().pos().starts_with(consts::esc::PATH) {
@@ -589,13 +1142,27 @@ impl Parser {
         let mut alias_str = String::new();
         let start = self.src().coord();
         let mut first = true;
+        // the depth of bracketed segments, whose keys are other aliases
+        let mut depth = 0;
 
         loop {
             self.unexpected_eof(|| Error::UnterminatedAlias(
                 start, self.src().file().to_owned(),
             ))?;
 
-            if !self.starts_with_alias_char() {
+            let bracket = match self.src().pos().chars().next() {
+                Some('[') if !first => {
+                    depth += 1;
+                    true
+                },
+                Some(']') if depth > 0 => {
+                    depth -= 1;
+                    true
+                },
+                _ => false,
+            };
+
+            if !bracket && !self.starts_with_alias_char() {
                 if first {
                     return Err(self.illegal_character(tag_name));
                 }
@@ -607,6 +1174,12 @@ impl Parser {
             first = false;
         }
 
+        if depth > 0 {
+            return Err(Error::UnterminatedAlias(
+                start, self.src().file().to_owned(),
+            ));
+        }
+
         if alias_str.is_empty() {
             return Err(Error::EmptyAlias(
                 start, self.src().file().to_owned()
@@ -673,7 +1246,7 @@ impl Parser {
 
         self.src_mut().take(1);
 
-        self.extends = Some(path);
+        self.extends = Some((path, start));
 
         Ok(true)
     }
@@ -709,7 +1282,11 @@ impl Parser {
             self.src().file().to_owned(),
         ))?;
 
-        let as_name = if self.src().pos().starts_with(consts::block::MODIFIER) {
+        let mut as_name = None;
+        let mut schema_path = None;
+        let mut format = None;
+        let mut merge = None;
+        while self.src().pos().starts_with(consts::block::MODIFIER) {
             self.src_mut().take(1);
             self.src_mut().trim_start();
             self.unexpected_eof(|| Error::UnterminatedTag(
@@ -718,11 +1295,18 @@ impl Parser {
                 self.src().file().to_owned(),
             ))?;
 
-            if !self.src().pos().starts_with(consts::modif::AS) {
-                return Err(self.illegal_character(TAG_NAME));
-            }
+            let modifier = [
+                consts::modif::AS, consts::modif::SCHEMA, consts::modif::FORMAT, consts::modif::MERGE,
+            ]
+                .into_iter()
+                .find(|m| self.src().pos().starts_with(m));
+
+            let modifier = match modifier {
+                Some(m) => m,
+                None => return Err(self.illegal_character(TAG_NAME)),
+            };
 
-            self.src_mut().take(consts::modif::AS.len());
+            self.src_mut().take(modifier.len());
             self.src_mut().trim_start();
             self.unexpected_eof(|| Error::UnterminatedTag(
                 TAG_NAME.to_owned(),
@@ -730,7 +1314,27 @@ impl Parser {
                 self.src().file().to_owned(),
             ))?;
 
-            let alias = self.alias("source")?;
+            if modifier == consts::modif::SCHEMA {
+                let path = self.pathlike(TAG_NAME, bypass)?;
+                schema_path = Some(self.normalize_path(path));
+            }
+            else if modifier == consts::modif::FORMAT {
+                let coord = self.src().coord();
+                let name = self.tag_string(TAG_NAME, start, bypass)?;
+                format = match Format::from_name(&name) {
+                    Some(format) => Some(format),
+                    None if bypass => None,
+                    None => return Err(Error::IllegalArgument(
+                        consts::modif::FORMAT.to_owned(), name, coord, self.file().to_owned(),
+                    )),
+                };
+            }
+            else if modifier == consts::modif::MERGE {
+                merge = Some(self.merge_mod(TAG_NAME, start, bypass)?);
+            }
+            else {
+                as_name = Some(self.alias("source")?);
+            }
 
             self.src_mut().trim_start();
             self.unexpected_eof(|| Error::UnterminatedTag(
@@ -738,12 +1342,7 @@ impl Parser {
                 start,
                 self.src().file().to_owned(),
             ))?;
-
-            Some(alias)
         }
-        else {
-            None
-        };
 
         if !self.src().pos().starts_with(consts::block::ENDTAG) {
             return Err(self.illegal_character("source"));
@@ -751,25 +1350,243 @@ impl Parser {
 
         self.src_mut().take(1);
 
-        if let Some(as_name) = as_name {
-            self.read_ctx_in_as(path, as_name)?;
+        if bypass {
+            return Ok(true);
         }
-        else {
-            self.read_ctx_in(path)?;
+
+        // the document is validated as it is read, before any alias is applied
+        let schema = match schema_path {
+            Some(schema_path) => Some(Rc::new(Schema::read(schema_path)?)),
+            None => self.context_schema.clone(),
+        };
+        let schema = schema.as_deref();
+
+        let read = if let Some(merge) = merge {
+            self.read_ctx_in_merged(path, as_name, format, schema, &merge)
         }
+        else if let Some(as_name) = as_name {
+            self.read_ctx_in_as(path, as_name, format, schema)
+        }
+        else {
+            self.read_ctx_in(path, format, schema)
+        };
+
+        read.map_err(|e| match e {
+            Error::SchemaViolation(v, d) => Error::SourceSchemaViolation(
+                v, d, start, self.file().to_owned(),
+            ),
+            e => e,
+        })?;
 
         Ok(true)
     }
 
-    fn include_content_mod(&mut self, start: Coordinate, bypass: bool) -> Result<Option<Vec<IncludeContentMod>>> {
-        if !self.src().pos().starts_with(consts::block::MODIFIER) {
+    /// The rules of a deep merge, as the optional array and null modes.
+    fn merge_mod(&mut self, tag: &str, start: Coordinate, bypass: bool) -> Result<Merge> {
+        self.src_mut().trim_start();
+        let arrays_coord = self.src().coord();
+        let arrays = self.tag_opt_string(tag, start, bypass)?;
+
+        let arrays = match arrays {
+            Some(name) => match ArrayMerge::from_name(&name) {
+                Some(arrays) => arrays,
+                None if bypass => ArrayMerge::default(),
+                None => return Err(Error::IllegalArgument(
+                    consts::modif::MERGE.to_owned(), name, arrays_coord, self.file().to_owned(),
+                )),
+            },
+            None => return Ok(Merge::default()),
+        };
+
+        self.src_mut().trim_start();
+        let nulls_coord = self.src().coord();
+        let nulls = match self.tag_opt_string(tag, start, bypass)? {
+            Some(name) => match NullMerge::from_name(&name) {
+                Some(nulls) => nulls,
+                None if bypass => NullMerge::default(),
+                None => return Err(Error::IllegalArgument(
+                    consts::modif::MERGE.to_owned(), name, nulls_coord, self.file().to_owned(),
+                )),
+            },
+            None => NullMerge::default(),
+        };
+
+        Ok(Merge { arrays, nulls, })
+    }
+
+    fn tag_opt_number<S>(&mut self, tag: &str, name: S, start: Coordinate) -> Result<Option<usize>>
+    where
+        S: AsRef<str>
+    {
+        const ZERO_THRU_NINE: [char; 10] = [
+            '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+        ];
+
+        let tag_name = format!("{tag} {}-modifier", name.as_ref());
+
+        self.src_mut().trim_start();
+        self.unexpected_eof(|| Error::UnterminatedTag(
+            tag_name.clone(),
+            start,
+            self.src().file().to_owned(),
+        ))?;
+
+        let coord = self.src().coord();
+        let mut number = String::new();
+        while !self.src().eof() &&
+            self.src().pos().starts_with(ZERO_THRU_NINE)
+        {
+            number.push_str(&self.src_mut().take(1).unwrap());
+        }
+
+        self.unexpected_eof(|| Error::UnterminatedTag(
+            tag_name.clone(),
+            start,
+            self.src().file().to_owned(),
+        ))?;
+
+        if number.is_empty() {
             return Ok(None);
         }
 
-        let mut mods = Vec::new();
+        number.parse::<usize>().map(Some).map_err(|_| Error::IllegalArgument(
+            name.as_ref().to_owned(), number.clone(), coord, self.file().to_owned()
+        ))
+    }
 
-        while self.src().pos().starts_with(consts::block::MODIFIER) {
-            self.src_mut().take(1);
+    fn tag_number<S>(&mut self, tag: &str, name: S, start: Coordinate) -> Result<usize>
+    where
+        S: AsRef<str>
+    {
+        match self.tag_opt_number(tag, name.as_ref(), start)? {
+            Some(n) => Ok(n),
+            None => Err(Error::IllegalCharacter(
+                format!("{tag} {}-modifier", name.as_ref()),
+                self.src().pos().chars().next().unwrap(),
+                self.src().coord(),
+                self.src().file().to_owned()
+            )),
+        }
+    }
+
+    fn modifier_opt_number<S>(&mut self, name: S, start: Coordinate) -> Result<Option<usize>>
+    where
+        S: AsRef<str>
+    {
+        self.tag_opt_number("include-content", name, start)
+    }
+
+    fn modifier_number<S>(&mut self, name: S, start: Coordinate) -> Result<usize>
+    where
+        S: AsRef<str>
+    {
+        self.tag_number("include-content", name, start)
+    }
+
+    /// Read the number of a modifier, which may be no greater than `max`.
+    fn modifier_bounded(&mut self, name: &str, start: Coordinate, max: usize) -> Result<usize> {
+        self.src_mut().trim_start();
+        let coord = self.src().coord();
+        let n = self.modifier_number(name, start)?;
+
+        if n > max {
+            return Err(Error::IllegalArgument(
+                name.to_owned(), n.to_string(), coord, self.file().to_owned(),
+            ));
+        }
+
+        Ok(n)
+    }
+
+    /// Read a limit, offset, or batch modifier of a loop.
+    fn loop_slice(&mut self, tag: &str, start: Coordinate, bypass: bool) -> Result<Option<LoopSlice>> {
+        if self.src().pos().starts_with(consts::modif::LIMIT) {
+            self.src_mut().take(consts::modif::LIMIT.len());
+            Ok(Some(LoopSlice::Limit(self.tag_number(tag, consts::modif::LIMIT, start)?)))
+        }
+        else if self.src().pos().starts_with(consts::modif::OFFSET) {
+            self.src_mut().take(consts::modif::OFFSET.len());
+            Ok(Some(LoopSlice::Offset(self.tag_number(tag, consts::modif::OFFSET, start)?)))
+        }
+        else if self.src().pos().starts_with(consts::modif::BATCH) {
+            self.src_mut().take(consts::modif::BATCH.len());
+
+            self.src_mut().trim_start();
+            let coord = self.src().coord();
+            let size = self.tag_number(tag, consts::modif::BATCH, start)?;
+
+            if size == 0 && !bypass {
+                return Err(Error::IllegalArgument(
+                    consts::modif::BATCH.to_owned(), size.to_string(), coord,
+                    self.file().to_owned(),
+                ));
+            }
+
+            Ok(Some(LoopSlice::Batch(size.max(1))))
+        }
+        else {
+            Ok(None)
+        }
+    }
+
+    fn modifier_rounding<S>(&mut self, name: S, start: Coordinate, bypass: bool) -> Result<Rounding>
+    where
+        S: AsRef<str>
+    {
+        self.src_mut().trim_start();
+        let coord = self.src().coord();
+
+        match self.modifier_opt_string(start, bypass)? {
+            None => Ok(Rounding::HalfUp),
+            Some(mode) => match Rounding::from_name(&mode) {
+                Some(r) => Ok(r),
+                None if bypass => Ok(Rounding::HalfUp),
+                None => Err(Error::IllegalArgument(
+                    name.as_ref().to_owned(), mode, coord, self.file().to_owned()
+                )),
+            },
+        }
+    }
+
+    fn tag_opt_string(&mut self, tag: &str, start: Coordinate, bypass: bool) -> Result<Option<String>> {
+        self.src_mut().trim_start();
+        self.unexpected_eof(|| Error::UnterminatedTag(
+            tag.to_owned(),
+            start,
+            self.src().file().to_owned(),
+        ))?;
+
+        if !self.src().pos().starts_with(consts::PATH) {
+            return Ok(None);
+        }
+
+        Ok(Some(self.path(bypass)?))
+    }
+
+    fn tag_string(&mut self, tag: &str, start: Coordinate, bypass: bool) -> Result<String> {
+        match self.tag_opt_string(tag, start, bypass)? {
+            Some(s) => Ok(s),
+            None => Err(self.illegal_character(tag)),
+        }
+    }
+
+    fn modifier_opt_string(&mut self, start: Coordinate, bypass: bool) -> Result<Option<String>> {
+        self.tag_opt_string("include-content", start, bypass)
+    }
+
+    fn modifier_string(&mut self, start: Coordinate, bypass: bool) -> Result<String> {
+        self.tag_string("include-content", start, bypass)
+    }
+
+    fn include_content_mod(&mut self, start: Coordinate, bypass: bool) -> Result<Option<Vec<IncludeContentMod>>> {
+        if !self.src().pos().starts_with(consts::block::MODIFIER) {
+            return Ok(None);
+        }
+
+        let mut mods = Vec::new();
+
+        while self.src().pos().starts_with(consts::block::MODIFIER) {
+            self.src_mut().take(1);
             self.src_mut().trim_start();
             self.unexpected_eof(|| Error::UnterminatedTag(
                 "include-content".to_owned(),
@@ -801,106 +1618,245 @@ impl Parser {
                 self.src_mut().take(consts::modif::JSON.len());
                 mods.push(IncludeContentMod::Json);
             }
+            else if self.src().pos().starts_with(consts::modif::RAW) {
+                self.src_mut().take(consts::modif::RAW.len());
+                mods.push(IncludeContentMod::Raw);
+            }
+            else if self.src().pos().starts_with(consts::modif::HTML) {
+                self.src_mut().take(consts::modif::HTML.len());
+                mods.push(IncludeContentMod::Escape(Escape::Html));
+            }
+            else if self.src().pos().starts_with(consts::modif::ATTR) {
+                self.src_mut().take(consts::modif::ATTR.len());
+                mods.push(IncludeContentMod::Escape(Escape::Attr));
+            }
+            else if self.src().pos().starts_with(consts::modif::URL) {
+                self.src_mut().take(consts::modif::URL.len());
+                mods.push(IncludeContentMod::Escape(Escape::Url));
+            }
+            // must follow json, which shares a prefix
+            else if self.src().pos().starts_with(consts::modif::JS) {
+                self.src_mut().take(consts::modif::JS.len());
+                mods.push(IncludeContentMod::Escape(Escape::Js));
+            }
+            else if self.src().pos().starts_with(consts::modif::CSV) {
+                self.src_mut().take(consts::modif::CSV.len());
+                mods.push(IncludeContentMod::Escape(Escape::Csv));
+            }
+            else if self.src().pos().starts_with(consts::modif::SH) {
+                self.src_mut().take(consts::modif::SH.len());
+                mods.push(IncludeContentMod::Escape(Escape::Sh));
+            }
             else if self.src().pos().starts_with(consts::modif::SPLIT) {
                 self.src_mut().take(consts::modif::SPLIT.len());
-                self.src_mut().trim_start();
-                self.unexpected_eof(|| Error::UnterminatedTag(
-                    "include-content split-modifier".to_owned(),
-                    start,
-                    self.src().file().to_owned(),
-                ))?;
 
-                const ZERO_THRU_NINE: [char; 10] = [
-                    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
-                ];
+                let split_into = self.modifier_number(consts::modif::SPLIT, start)?;
+                let split_idx = self.modifier_number(consts::modif::SPLIT, start)?;
 
-                let mut split_into = String::new();
-                while !self.src().eof() &&
-                    self.src().pos().starts_with(ZERO_THRU_NINE)
-                {
-                    split_into.push_str(&self.src_mut().take(1).unwrap());
+                if split_into < 2 || split_idx >= split_into {
+                    return Err(Error::IllegalSplit(
+                        split_into, split_idx, self.src().coord(), self.file().to_owned()
+                    ));
                 }
 
-                self.unexpected_eof(|| Error::UnterminatedTag(
-                    "include-content split-modifier".to_owned(),
-                    start,
-                    self.src().file().to_owned(),
-                ))?;
+                mods.push(IncludeContentMod::Split(split_into, split_idx));
+            }
+            else if self.src().pos().starts_with(consts::modif::REPLACE) {
+                self.src_mut().take(consts::modif::REPLACE.len());
 
-                if split_into.is_empty() {
-                    return Err(Error::IllegalCharacter(
-                        "include-content split-modifier".to_owned(),
-                        self.src().pos().chars().next().unwrap(),
-                        self.src().coord(),
-                        self.src().file().to_owned()
-                    ));
-                }
+                let from = self.modifier_string(start, bypass)?;
+                let to = self.modifier_string(start, bypass)?;
 
-                let split_into = split_into.parse::<usize>().unwrap();
+                mods.push(IncludeContentMod::Replace(from, to));
+            }
+            else if self.src().pos().starts_with(consts::modif::TRUNCATE) {
+                self.src_mut().take(consts::modif::TRUNCATE.len());
 
-                self.src_mut().trim_start();
+                let len = self.modifier_number(consts::modif::TRUNCATE, start)?;
+                let suffix = self.modifier_opt_string(start, bypass)?
+                    .unwrap_or_default();
 
-                let mut split_idx = String::new();
-                while !self.src().eof() &&
-                    self.src().pos().starts_with(ZERO_THRU_NINE)
-                {
-                    split_idx.push_str(&self.src_mut().take(1).unwrap());
+                mods.push(IncludeContentMod::Truncate(len, suffix));
+            }
+            else if self.src().pos().starts_with(consts::modif::CAPITALIZE) {
+                self.src_mut().take(consts::modif::CAPITALIZE.len());
+                mods.push(IncludeContentMod::Capitalize);
+            }
+            else if self.src().pos().starts_with(consts::modif::TITLE) {
+                self.src_mut().take(consts::modif::TITLE.len());
+                mods.push(IncludeContentMod::Title);
+            }
+            else if self.src().pos().starts_with(consts::modif::SLUGIFY) {
+                self.src_mut().take(consts::modif::SLUGIFY.len());
+                mods.push(IncludeContentMod::Slugify);
+            }
+            else if self.src().pos().starts_with(consts::modif::PAD_LEFT) ||
+                self.src().pos().starts_with(consts::modif::PAD_RIGHT)
+            {
+                let is_left = self.src().pos().starts_with(consts::modif::PAD_LEFT);
+                let name = if is_left {
+                    consts::modif::PAD_LEFT
                 }
+                else {
+                    consts::modif::PAD_RIGHT
+                };
 
-                self.unexpected_eof(|| Error::UnterminatedTag(
-                    "include-content split-modifier".to_owned(),
-                    start,
-                    self.src().file().to_owned(),
-                ))?;
+                self.src_mut().take(name.len());
+
+                let width = self.modifier_bounded(name, start, consts::MAX_MODIFIER_LEN)?;
+                self.src_mut().trim_start();
+                let pad_coord = self.src().coord();
+                let pad = self.modifier_opt_string(start, bypass)?
+                    .unwrap_or(" ".to_owned());
 
-                if split_idx.is_empty() {
-                    return Err(Error::IllegalCharacter(
-                        "include-content split-modifier".to_owned(),
-                        self.src().pos().chars().next().unwrap(),
-                        self.src().coord(),
-                        self.src().file().to_owned()
+                if pad.is_empty() && !bypass {
+                    return Err(Error::IllegalArgument(
+                        name.to_owned(), pad, pad_coord, self.file().to_owned()
                     ));
                 }
 
-                let split_idx = split_idx.parse::<usize>().unwrap();
-
-                if split_into < 2 || split_idx >= split_into {
-                    return Err(Error::IllegalSplit(
-                        split_into, split_idx, self.src().coord(), self.file().to_owned()
-                    ));
+                if is_left {
+                    mods.push(IncludeContentMod::PadLeft(width, pad));
+                }
+                else {
+                    mods.push(IncludeContentMod::PadRight(width, pad));
                 }
+            }
+            else if self.src().pos().starts_with(consts::modif::SUBSTRING) {
+                self.src_mut().take(consts::modif::SUBSTRING.len());
 
-                mods.push(IncludeContentMod::Split(split_into, split_idx));
+                let sub_start = self.modifier_number(consts::modif::SUBSTRING, start)?;
+                let sub_len = self.modifier_number(consts::modif::SUBSTRING, start)?;
+
+                mods.push(IncludeContentMod::Substring(sub_start, sub_len));
             }
-            else if self.src().pos().starts_with(consts::modif::REPLACE) {
-                self.src_mut().take(consts::modif::REPLACE.len());
-                self.src_mut().trim_start();
-                self.unexpected_eof(|| Error::UnterminatedTag(
-                    "include-content".to_owned(),
-                    start,
-                    self.src().file().to_owned(),
-                ))?;
+            else if self.src().pos().starts_with(consts::modif::REPEAT) {
+                self.src_mut().take(consts::modif::REPEAT.len());
 
-                if !self.src().pos().starts_with(consts::PATH) {
-                    return Err(self.illegal_character("include-content"));
-                }
+                let times = self.modifier_number(consts::modif::REPEAT, start)?;
+
+                mods.push(IncludeContentMod::Repeat(times));
+            }
+            else if self.src().pos().starts_with(consts::modif::STRIP_PREFIX) {
+                self.src_mut().take(consts::modif::STRIP_PREFIX.len());
+
+                let prefix = self.modifier_string(start, bypass)?;
+
+                mods.push(IncludeContentMod::StripPrefix(prefix));
+            }
+            else if self.src().pos().starts_with(consts::modif::STRIP_SUFFIX) {
+                self.src_mut().take(consts::modif::STRIP_SUFFIX.len());
+
+                let suffix = self.modifier_string(start, bypass)?;
 
-                let from = self.path(bypass)?;
+                mods.push(IncludeContentMod::StripSuffix(suffix));
+            }
+            else if self.src().pos().starts_with(consts::modif::WORDCOUNT) {
+                self.src_mut().take(consts::modif::WORDCOUNT.len());
+                mods.push(IncludeContentMod::WordCount);
+            }
+            else if self.src().pos().starts_with(consts::modif::ESCAPE_NEWLINES) {
+                self.src_mut().take(consts::modif::ESCAPE_NEWLINES.len());
+                mods.push(IncludeContentMod::EscapeNewlines);
+            }
+            else if self.src().pos().starts_with(consts::modif::FIXED) {
+                self.src_mut().take(consts::modif::FIXED.len());
+
+                let places = self.modifier_bounded(consts::modif::FIXED, start, consts::MAX_PLACES)?;
+                let mode = self.modifier_rounding(consts::modif::FIXED, start, bypass)?;
+
+                mods.push(IncludeContentMod::Fixed(places, mode));
+            }
+            else if self.src().pos().starts_with(consts::modif::THOUSANDS) {
+                self.src_mut().take(consts::modif::THOUSANDS.len());
+
+                let sep = self.modifier_opt_string(start, bypass)?;
+                let point = match sep {
+                    Some(_) => self.modifier_opt_string(start, bypass)?,
+                    None => None,
+                };
+
+                mods.push(IncludeContentMod::Thousands(
+                    sep.unwrap_or(",".to_owned()),
+                    point.unwrap_or(".".to_owned()),
+                ));
+            }
+            else if self.src().pos().starts_with(consts::modif::PERCENT) {
+                self.src_mut().take(consts::modif::PERCENT.len());
 
                 self.src_mut().trim_start();
-                self.unexpected_eof(|| Error::UnterminatedTag(
-                    "include-content".to_owned(),
-                    start,
-                    self.src().file().to_owned(),
-                ))?;
+                let places_coord = self.src().coord();
+                let places = self.modifier_opt_number(consts::modif::PERCENT, start)?;
 
-                if !self.src().pos().starts_with(consts::PATH) {
-                    return Err(self.illegal_character("include-content"));
+                if let Some(places) = places.filter(|places| *places > consts::MAX_PLACES) {
+                    return Err(Error::IllegalArgument(
+                        consts::modif::PERCENT.to_owned(), places.to_string(),
+                        places_coord, self.file().to_owned(),
+                    ));
                 }
+                let mode = match places {
+                    Some(_) => self.modifier_rounding(consts::modif::PERCENT, start, bypass)?,
+                    None => Rounding::HalfUp,
+                };
+
+                mods.push(IncludeContentMod::Percent(places.unwrap_or(0), mode));
+            }
+            else if self.src().pos().starts_with(consts::modif::BYTES) {
+                self.src_mut().take(consts::modif::BYTES.len());
+                mods.push(IncludeContentMod::Bytes);
+            }
+            else if self.src().pos().starts_with(consts::modif::JOIN) {
+                self.src_mut().take(consts::modif::JOIN.len());
 
-                let to = self.path(bypass)?;
+                let sep = self.modifier_string(start, bypass)?;
 
-                mods.push(IncludeContentMod::Replace(from, to));
+                mods.push(IncludeContentMod::Join(sep));
+            }
+            else if self.src().pos().starts_with(consts::modif::LENGTH) {
+                self.src_mut().take(consts::modif::LENGTH.len());
+                mods.push(IncludeContentMod::Length);
+            }
+            else if self.src().pos().starts_with(consts::modif::FIRST) {
+                self.src_mut().take(consts::modif::FIRST.len());
+                mods.push(IncludeContentMod::First);
+            }
+            else if self.src().pos().starts_with(consts::modif::LAST) {
+                self.src_mut().take(consts::modif::LAST.len());
+                mods.push(IncludeContentMod::Last);
+            }
+            else if self.src().pos().starts_with(consts::modif::NTH) {
+                self.src_mut().take(consts::modif::NTH.len());
+
+                let idx = self.modifier_number(consts::modif::NTH, start)?;
+
+                mods.push(IncludeContentMod::Nth(idx));
+            }
+            else if self.src().pos().starts_with(consts::modif::SLICE) {
+                self.src_mut().take(consts::modif::SLICE.len());
+
+                let slice_start = self.modifier_number(consts::modif::SLICE, start)?;
+                let slice_end = self.modifier_number(consts::modif::SLICE, start)?;
+
+                mods.push(IncludeContentMod::Slice(slice_start, slice_end));
+            }
+            else if self.src().pos().starts_with(consts::modif::REVERSE) {
+                self.src_mut().take(consts::modif::REVERSE.len());
+                mods.push(IncludeContentMod::Reverse);
+            }
+            else if self.src().pos().starts_with(consts::modif::DATE) {
+                self.src_mut().take(consts::modif::DATE.len());
+
+                self.src_mut().trim_start();
+                let coord = self.src().coord();
+                let format = self.modifier_string(start, bypass)?;
+
+                match DateFormat::parse(&format) {
+                    Some(f) => mods.push(IncludeContentMod::Date(f)),
+                    None if bypass => {},
+                    None => return Err(Error::IllegalArgument(
+                        consts::modif::DATE.to_owned(), format, coord, self.file().to_owned()
+                    )),
+                }
             }
             else {
                 return Err(self.illegal_character("include-content"));
@@ -917,6 +1873,80 @@ impl Parser {
         Ok(Some(mods))
     }
 
+    /// Apply a modifier which parses the value, such as a number or date,
+    /// returning `error` when it can't be parsed. An empty value is allowed
+    /// when the include-content tag is nullable or bypassed.
+    fn parsed_mod<F, E>(value: String, allow_empty: bool, error: E, f: F) -> Result<String>
+    where
+        F: FnOnce(&str) -> Option<String>,
+        E: FnOnce() -> Error,
+    {
+        if allow_empty && value.is_empty() {
+            return Ok(value);
+        }
+
+        f(&value).ok_or_else(error)
+    }
+
+    /// Write a json value as a string, serializing it if the json modifier is
+    /// used.
+    fn json_to_string(value: JsonValue, is_json: bool) -> String {
+        match value {
+            v if is_json => v.to_string(),
+            JsonValue::String(s) => s,
+            JsonValue::Null => String::new(),
+            v => v.to_string(),
+        }
+    }
+
+    /// Apply an array modifier to a json value, which passes through when it
+    /// is empty and `allow_empty` is set.
+    fn array_mod(alias: &str, value: JsonValue, m: IncludeContentMod, allow_empty: bool) -> Result<JsonValue> {
+        let is_empty = match &value {
+            JsonValue::Null => true,
+            JsonValue::String(s) => s.is_empty(),
+            _ => false,
+        };
+
+        if allow_empty && is_empty {
+            return Ok(value);
+        }
+
+        let array = |value: JsonValue| match value {
+            JsonValue::Array(arr) => Ok(arr),
+            _ => Err(Error::ValueNotArray(alias.into())),
+        };
+
+        match m {
+            IncludeContentMod::Join(sep) => Ok(array(value)?.into_iter()
+                .map(|v| Self::json_to_string(v, false))
+                .collect::<Vec<String>>()
+                .join(&sep)
+                .into()),
+            IncludeContentMod::Length => match value {
+                JsonValue::String(s) => Ok(transform::length(s).into()),
+                v => Ok(array(v)?.len().into()),
+            },
+            IncludeContentMod::First => Ok(array(value)?.into_iter()
+                .next()
+                .unwrap_or(JsonValue::Null)),
+            IncludeContentMod::Last => Ok(array(value)?.pop()
+                .unwrap_or(JsonValue::Null)),
+            IncludeContentMod::Nth(idx) => Ok(array(value)?.into_iter()
+                .nth(idx)
+                .unwrap_or(JsonValue::Null)),
+            IncludeContentMod::Slice(start, end) => Ok(JsonValue::Array(array(value)?.into_iter()
+                .skip(start)
+                .take(end.saturating_sub(start))
+                .collect())),
+            IncludeContentMod::Reverse => match value {
+                JsonValue::String(s) => Ok(transform::reverse(s).into()),
+                v => Ok(JsonValue::Array(array(v)?.into_iter().rev().collect())),
+            },
+            _ => Ok(value),
+        }
+    }
+
     fn include_content(&mut self, bypass: bool) -> Result<bool> {
         if !self.src().pos().starts_with(consts::block::INCLUDE_CONTENT) {
             return Ok(false);
@@ -971,41 +2001,103 @@ impl Parser {
             false
         };
 
-        let mut value = if bypass {
+        // explicitly escaped content is not escaped again
+        let is_raw = if let Some(mods) = mods.as_ref() {
+            mods.iter().any(|m| matches!(m, IncludeContentMod::Raw|IncludeContentMod::Escape(_)))
+        }
+        else {
+            false
+        };
+
+        // $content is output which has already been parsed, never escape it
+        let escape = if is_raw || alias == consts::CONTENT {
+            Escape::None
+        }
+        else {
+            self.escape_mode()
+        };
+
+        let is_array = if let Some(mods) = mods.as_ref() {
+            mods.iter().any(|m| m.is_array_mod())
+        }
+        else {
+            false
+        };
+
+        // array modifiers operate on the json value until a string modifier
+        // is applied
+        let mut array_value = if bypass || !is_array {
+            None
+        }
+        else if nullable {
+            Some(self.optional_context(|ctx| Ok(Some(ctx.get_value(&alias)?.clone())))?
+                .unwrap_or(JsonValue::Null))
+        }
+        else {
+            Some(self.enforce_context(|ctx| Ok(ctx.get_value(&alias)?.clone()))?)
+        };
+
+        let mut value = if bypass || is_array {
             "".to_owned()
         }
         else if nullable && is_json {
-            self.optional_context(|ctx| Ok(Some(ctx.get_value(alias)?.clone())))?
+            self.optional_context(|ctx| Ok(Some(ctx.get_value(&alias)?.clone())))?
                 .unwrap_or(JsonValue::Null)
                 .to_string()
         }
         else if nullable && is_path {
-            self.optional_context(|ctx| ctx.get_path_opt(alias))?
+            self.optional_context(|ctx| ctx.get_path_opt(&alias))?
                 .unwrap_or(PathBuf::new())
                 .to_str()
                 .unwrap_or("")
                 .to_owned()
         }
         else if is_json {
-            self.enforce_context(|ctx| Ok(ctx.get_value(alias)?.clone()))?
+            self.enforce_context(|ctx| Ok(ctx.get_value(&alias)?.clone()))?
                 .to_string()
         }
         else if is_path {
-            self.enforce_context(|ctx| ctx.get_path(alias))?
+            self.enforce_context(|ctx| ctx.get_path(&alias))?
                 .to_str()
                 .unwrap_or("")
                 .to_owned()
         }
         else if nullable {
-            self.optional_context(|ctx| ctx.get_stringlike_opt(alias))?
+            self.optional_context(|ctx| ctx.get_stringlike_opt(&alias))?
                 .unwrap_or(String::new())
         }
         else {
-            self.enforce_context(|ctx| ctx.get_stringlike(alias))?
+            self.enforce_context(|ctx| ctx.get_stringlike(&alias))?
         };
 
+        // explicit escaping is applied after every other modifier, so that
+        // none can cut through or change the case of an escape sequence
+        let explicit_escapes = mods.iter()
+            .flatten()
+            .filter_map(|m| match m {
+                IncludeContentMod::Escape(e) => Some(*e),
+                _ => None,
+            })
+            .collect::<Vec<Escape>>();
+
+        let not_number = || Error::ValueNotNumber(alias.as_str().into());
+        let not_date = || Error::ValueNotDate(alias.as_str().into());
+
         if let Some(mods) = mods {
             for m in mods {
+                if m.is_array_mod() {
+                    let current = array_value.take()
+                        .unwrap_or(JsonValue::String(value));
+
+                    array_value = Some(Self::array_mod(&alias, current, m, bypass || nullable)?);
+                    value = String::new();
+                    continue;
+                }
+
+                if let Some(v) = array_value.take() {
+                    value = Self::json_to_string(v, is_json);
+                }
+
                 value = match m {
                     IncludeContentMod::Upper => value.to_uppercase(),
                     IncludeContentMod::Lower => value.to_lowercase(),
@@ -1013,6 +2105,8 @@ impl Parser {
                         .replace(&from, &to),
                     IncludeContentMod::Path => value,
                     IncludeContentMod::Json => value,
+                    IncludeContentMod::Raw => value,
+                    IncludeContentMod::Escape(_) => value,
                     IncludeContentMod::Filename => {
                         let p = PathBuf::from(value);
                         p.file_stem().and_then(|f| f.to_str())
@@ -1049,10 +2143,75 @@ impl Parser {
                         value[start_end.0..start_end.1].to_owned()
                     },
                     IncludeContentMod::Trim => value.trim().to_owned(),
+                    IncludeContentMod::Truncate(len, suffix) => transform::truncate(
+                        value, len, &suffix
+                    ),
+                    IncludeContentMod::Capitalize => transform::capitalize(value),
+                    IncludeContentMod::Title => transform::title(value),
+                    IncludeContentMod::Slugify => transform::slugify(value),
+                    IncludeContentMod::PadLeft(width, pad) => transform::pad_left(
+                        value, width, &pad
+                    ),
+                    IncludeContentMod::PadRight(width, pad) => transform::pad_right(
+                        value, width, &pad
+                    ),
+                    IncludeContentMod::Substring(start, len) => transform::substring(
+                        value, start, len
+                    ),
+                    IncludeContentMod::Repeat(times) => match value.len().checked_mul(times) {
+                        Some(len) if len <= consts::MAX_MODIFIER_LEN => value.repeat(times),
+                        _ => return Err(Error::IllegalArgument(
+                            consts::modif::REPEAT.to_owned(), times.to_string(),
+                            self.src().coord(), self.file().to_owned(),
+                        )),
+                    },
+                    IncludeContentMod::StripPrefix(prefix) => value.strip_prefix(&prefix)
+                        .map(|v| v.to_owned())
+                        .unwrap_or(value),
+                    IncludeContentMod::StripSuffix(suffix) => value.strip_suffix(&suffix)
+                        .map(|v| v.to_owned())
+                        .unwrap_or(value),
+                    IncludeContentMod::WordCount => transform::wordcount(value).to_string(),
+                    IncludeContentMod::EscapeNewlines => transform::escape_newlines(value),
+                    IncludeContentMod::Fixed(places, mode) => Self::parsed_mod(
+                        value, bypass || nullable, not_number, |v| number::fixed(v, places, mode)
+                    )?,
+                    IncludeContentMod::Thousands(sep, point) => Self::parsed_mod(
+                        value, bypass || nullable, not_number, |v| number::thousands(v, &sep, &point)
+                    )?,
+                    IncludeContentMod::Percent(places, mode) => Self::parsed_mod(
+                        value, bypass || nullable, not_number, |v| number::percent(v, places, mode)
+                    )?,
+                    IncludeContentMod::Bytes => Self::parsed_mod(
+                        value, bypass || nullable, not_number, |v| number::bytes(v)
+                    )?,
+                    IncludeContentMod::Date(format) => Self::parsed_mod(
+                        value, bypass || nullable, not_date, |v| date::format(v, &format)
+                    )?,
+                    // applied to the json value above
+                    IncludeContentMod::Join(_)|IncludeContentMod::Length|
+                    IncludeContentMod::First|IncludeContentMod::Last|
+                    IncludeContentMod::Nth(_)|IncludeContentMod::Slice(_, _)|
+                    IncludeContentMod::Reverse => value,
                 }
             }
         }
 
+        if let Some(v) = array_value {
+            value = Self::json_to_string(v, is_json);
+        }
+
+        for e in explicit_escapes {
+            value = e.escape(value);
+        }
+
+        let value = if is_json {
+            escape.escape_json(value)
+        }
+        else {
+            escape.escape(value)
+        };
+
         self.output.push_str(&value);
 
         Ok(true)
@@ -1138,7 +2297,10 @@ impl Parser {
             Ok(String::new())
         }
         else if is_raw && is_md {
-            NfmParser::parse_file(&path).map_err(|e| Error::IO(e, path.as_ref().into()))
+            // front matter is left out of the rendered markdown
+            let mut source = Source::read_file(&path)?;
+            source.front_matter();
+            Ok(NfmParser::parse_str(&source.remaining()))
         }
         else if is_raw {
             read_file(path)
@@ -1205,11 +2367,13 @@ impl Parser {
         let has_block = !self.trim_or_end();
 
         if !has_block {
-            let output = self.include_file_parse(path, is_raw, is_md, bypass)?;
+            let output = self.include_file_parse(&path, is_raw, is_md, bypass)
+                .map_err(|e| self.at_include_site(&path, start, e))?;
             self.output.push_str(&output);
             return Ok(true);
         }
 
+        let template = path.clone();
         let output = self.spawn_sealed_internal_parser(|p| {
             while !p.src().eof() && !p.src().pos().starts_with(consts::block::ENDBLOCK) {
                 p.parse_next(bypass)?;
@@ -1224,13 +2388,27 @@ impl Parser {
             let mut file_output = p.include_file_parse(path, is_raw, is_md, bypass)?;
             std::mem::swap(&mut file_output, &mut p.output);
             Ok(())
-        })?;
+        }).map_err(|e| self.at_include_site(&template, start, e))?;
 
         self.output.push_str(&output);
 
         Ok(true)
     }
 
+    /// Report an error in the parameters of an included or extended template
+    /// at the tag which parsed it.
+    fn at_include_site(&self, template: &Path, start: Coordinate, e: Error) -> Error {
+        match e {
+            Error::MissingTemplateParameter(param, t, _, _) if t == template => {
+                Error::MissingTemplateParameter(param, t, start, self.file().to_owned())
+            },
+            Error::TemplateParameterType(param, ty, t, _, _) if t == template => {
+                Error::TemplateParameterType(param, ty, t, start, self.file().to_owned())
+            },
+            e => e,
+        }
+    }
+
     fn if_condition(&mut self) -> IfCondition {
         if self.src().pos().starts_with(consts::exp::EXISTS) {
             self.src_mut().take(consts::exp::EXISTS.len());
@@ -1368,35 +2546,31 @@ impl Parser {
         }
     }
 
-    fn if_tag(&mut self, bypass: bool) -> Result<bool> {
-        if !self.src().pos().starts_with(consts::block::IF) {
-            return Ok(false);
-        }
-
-        let start = self.src().coord();
-        const TAG_NAME: &str = "if";
+    /// Parse a chain of conditions, evaluating them if `evaluate` is set.
+    fn condition<S>(&mut self, tag_name: S, start: Coordinate, evaluate: bool) -> Result<bool>
+    where
+        S: AsRef<str>
+    {
+        let tag_name = tag_name.as_ref();
 
-        fn unexpected_eof_if(p: &mut Parser, coords: Coordinate) -> Result<()> {
+        fn unexpected_eof_cond(p: &mut Parser, tag_name: &str, coords: Coordinate) -> Result<()> {
             p.unexpected_eof(|| Error::UnterminatedTag(
-                TAG_NAME.to_owned(),
+                tag_name.to_owned(),
                 coords,
                 p.src().file().to_owned(),
             ))
         }
 
-        // take if block
-        self.src_mut().take(consts::block::IF.len());
-
         let mut is_true = false;
-        let mut bypass_rest_of_if = false;
+        let mut bypass_rest = !evaluate;
         loop {
             self.src_mut().trim_start();
-            unexpected_eof_if(self, start)?;
+            unexpected_eof_cond(self, tag_name, start)?;
 
             let negate = if self.src().pos().starts_with(consts::exp::NOT) {
                 self.src_mut().take(1);
                 self.src_mut().trim_start();
-                unexpected_eof_if(self, start)?;
+                unexpected_eof_cond(self, tag_name, start)?;
 
                 true
             }
@@ -1404,35 +2578,35 @@ impl Parser {
                 false
             };
 
-            let alias = self.alias(TAG_NAME)?;
+            let alias = self.alias(tag_name)?;
 
             self.src_mut().trim_start();
-            unexpected_eof_if(self, start)?;
+            unexpected_eof_cond(self, tag_name, start)?;
 
             let condition = self.if_condition();
             self.src_mut().trim_start();
-            unexpected_eof_if(self, start)?;
+            unexpected_eof_cond(self, tag_name, start)?;
 
             let other_alias = match condition {
                 IfCondition::Eq|IfCondition::Ne|IfCondition::Gt|IfCondition::Ge|
-                IfCondition::Lt|IfCondition::Le => Some(self.alias(TAG_NAME)?),
+                IfCondition::Lt|IfCondition::Le => Some(self.alias(tag_name)?),
                 IfCondition::Empty|IfCondition::Exists|IfCondition::Truthy => None
             };
 
-            if !bypass_rest_of_if {
+            if !bypass_rest {
                 is_true = self.if_is_true(negate, condition, alias, other_alias)?;
             }
 
             if self.src().pos().starts_with(consts::exp::AND) {
                 self.src_mut().take(consts::exp::AND.len());
-                if !bypass_rest_of_if && !is_true {
-                    bypass_rest_of_if = true;
+                if !bypass_rest && !is_true {
+                    bypass_rest = true;
                 }
             }
             else if self.src().pos().starts_with(consts::exp::OR) {
                 self.src_mut().take(consts::exp::OR.len());
-                if !bypass_rest_of_if && is_true {
-                    bypass_rest_of_if = true;
+                if !bypass_rest && is_true {
+                    bypass_rest = true;
                 }
             }
             else {
@@ -1440,13 +2614,37 @@ impl Parser {
             }
         }
 
-        if !self.src().pos().starts_with(consts::block::ENDTAG) {
-            return Err(self.illegal_character("if"));
+        Ok(is_true)
+    }
+
+    fn if_tag(&mut self, bypass: bool) -> Result<bool> {
+        if !self.src().pos().starts_with(consts::block::IF) {
+            return Ok(false);
         }
 
-        self.src_mut().take(1);
+        let start = self.src().coord();
+        const TAG_NAME: &str = "if";
 
-        self.do_trim_start("if", start)?;
+        fn unexpected_eof_if(p: &mut Parser, coords: Coordinate) -> Result<()> {
+            p.unexpected_eof(|| Error::UnterminatedTag(
+                TAG_NAME.to_owned(),
+                coords,
+                p.src().file().to_owned(),
+            ))
+        }
+
+        // take if block
+        self.src_mut().take(consts::block::IF.len());
+
+        let is_true = self.condition(TAG_NAME, start, true)?;
+
+        if !self.src().pos().starts_with(consts::block::ENDTAG) {
+            return Err(self.illegal_character("if"));
+        }
+
+        self.src_mut().take(1);
+
+        self.do_trim_start("if", start)?;
 
         // parse if contents
         let if_output = self.spawn_sealed_internal_parser(|p| {
@@ -1541,6 +2739,60 @@ impl Parser {
                 self.src_mut().take(consts::modif::DIRS.len());
                 mods.push(ForFileMod::Dirs);
             }
+            else if self.src().pos().starts_with(consts::modif::RECURSIVE) {
+                self.src_mut().take(consts::modif::RECURSIVE.len());
+                let depth = self.tag_opt_number(TAG_NAME, consts::modif::RECURSIVE, start)?;
+                mods.push(ForFileMod::Recursive(depth));
+            }
+            else if self.src().pos().starts_with(consts::modif::SORT_BY) {
+                self.src_mut().take(consts::modif::SORT_BY.len());
+                self.src_mut().trim_start();
+                unexpected_eof(self, start)?;
+
+                // files are sorted by a key of their entry or by a named
+                // property
+                let (key, natural) = if self.src().pos().starts_with(consts::PATH) {
+                    (self.path(bypass)?, false)
+                }
+                else if self.src().pos().starts_with(consts::modif::MTIME) {
+                    self.src_mut().take(consts::modif::MTIME.len());
+                    ("modified".to_owned(), false)
+                }
+                else if self.src().pos().starts_with(consts::modif::CTIME) {
+                    self.src_mut().take(consts::modif::CTIME.len());
+                    ("created".to_owned(), false)
+                }
+                else if self.src().pos().starts_with(consts::modif::SIZE) {
+                    self.src_mut().take(consts::modif::SIZE.len());
+                    ("size".to_owned(), false)
+                }
+                else if self.src().pos().starts_with(consts::modif::NAME_NATURAL) {
+                    self.src_mut().take(consts::modif::NAME_NATURAL.len());
+                    ("name".to_owned(), true)
+                }
+                else if self.src().pos().starts_with(consts::modif::NAME) {
+                    self.src_mut().take(consts::modif::NAME.len());
+                    ("name".to_owned(), false)
+                }
+                else {
+                    return Err(self.illegal_character(TAG_NAME));
+                };
+
+                let mut order = self.sort_order(TAG_NAME, Some(key), start)?;
+                order.natural |= natural;
+
+                mods.push(ForFileMod::Sort(order));
+            }
+            else if self.src().pos().starts_with(consts::modif::EXCLUDE) {
+                self.src_mut().take(consts::modif::EXCLUDE.len());
+                self.src_mut().trim_start();
+                let pattern = self.path(bypass)?;
+
+                mods.push(ForFileMod::Exclude(Glob::new(pattern)));
+            }
+            else if let Some(slice) = self.loop_slice(TAG_NAME, start, bypass)? {
+                mods.push(ForFileMod::Slice(slice));
+            }
             else {
                 return Err(self.illegal_character(TAG_NAME));
             }
@@ -1600,14 +2852,26 @@ impl Parser {
         Ok(())
     }
 
-    fn loop_context(&mut self, idx: usize, len: usize) -> Result<()> {
-        self.set_json_value("$loop.index", idx.into())?;
-        self.set_json_value("$loop.position", (idx + 1).into())?;
-        self.set_json_value("$loop.length", len.into())?;
-        self.set_json_value("$loop.max", (len - 1).into())?;
-        self.set_json_value("$loop.first", (idx == 0).into())?;
-        self.set_json_value("$loop.last", (idx == len - 1).into())?;
-        Ok(())
+    /// Replace the loop context with that of the item at `idx`, keeping the
+    /// context of an enclosing loop as its parent.
+    fn loop_context(&mut self, idx: usize, items: &[JsonValue]) -> Result<()> {
+        let len = items.len();
+        let parent = self.ctx().as_ref()
+            .and_then(|ctx| ctx.get("$loop").ok())
+            .cloned()
+            .unwrap_or(JsonValue::Null);
+
+        self.set_json_value("$loop", json!({
+            "index": idx,
+            "position": idx + 1,
+            "length": len,
+            "max": len - 1,
+            "first": idx == 0,
+            "last": idx == len - 1,
+            "prev": idx.checked_sub(1).map(|i| items[i].clone()),
+            "next": items.get(idx + 1).cloned(),
+            "parent": parent,
+        }))
     }
 
     fn for_file(&mut self, bypass: bool) -> Result<bool> {
@@ -1670,25 +2934,12 @@ impl Parser {
             )
             .unwrap_or(Vec::new());
 
-        let reverse = mods.as_ref()
-            .map(|m| m.iter()
-                .filter_map(|m| if let ForFileMod::Reverse = m {
-                    Some(())
-                }
-                else {
-                    None
-                })
-                .collect::<Vec<()>>()
-            )
-            .unwrap_or(Vec::new())
-            .len() % 2 != 0;
-
         let files_only = mods.as_ref()
             .map(|m| m.iter().any(|m| matches!(m, ForFileMod::Files)))
             .unwrap_or(false);
 
         let dirs_only = if !files_only {
-            mods
+            mods.as_ref()
                 .map(|m| m.iter().any(|m| matches!(m, ForFileMod::Dirs)))
                 .unwrap_or(false)
         }
@@ -1696,39 +2947,62 @@ impl Parser {
             false
         };
 
+        let recursive = mods.iter().flatten()
+            .find_map(|m| if let ForFileMod::Recursive(depth) = m {
+                Some(*depth)
+            }
+            else {
+                None
+            });
+
+        let exclude = mods.iter().flatten()
+            .filter_map(|m| if let ForFileMod::Exclude(glob) = m {
+                Some(glob.clone())
+            }
+            else {
+                None
+            })
+            .collect::<Vec<Glob>>();
+
+        // a pattern in the path is matched against the paths beneath the
+        // directory before it
+        let (root, glob) = Glob::split_path(&path);
+
+        let max_depth = match (&glob, recursive) {
+            (Some(glob), Some(Some(depth))) => glob.depth().map(|d| d.min(depth)).or(Some(depth)),
+            (Some(glob), _) => glob.depth(),
+            (None, Some(depth)) => depth,
+            (None, None) => Some(1),
+        };
+
         let mut items = if bypass {
             vec![]
         }
         else {
-            let p = path.clone();
-            path.read_dir().map_err(|e| Error::IO(e, p.clone()))?
-                .map(|entry_res| {
-                    let entry = entry_res.map_err(|e| Error::IO(e, p.clone()))?;
-                    let path = entry.path();
-                    let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_owned());
-                    let stem = path.file_stem().and_then(|f| f.to_str())
-                        .map(|f| f.to_owned());
-                    let name = path.file_name().and_then(|f| f.to_str())
-                        .map(|f| f.to_owned());
-
-                    if (files_only && !path.is_file()) ||
-                        (dirs_only && !path.is_dir()) ||
+            let mut found = Vec::new();
+            walk_dir(&root, &root, 1, max_depth, &exclude, &mut found)?;
+
+            found.into_iter()
+                .map(|(path, relative)| {
+                    if glob.as_ref().is_some_and(|g| !g.matches(&relative)) {
+                        return Ok(None);
+                    }
+
+                    let mut file = LoopFile::new(path, relative);
+
+                    if (files_only && !file.is_file) ||
+                        (dirs_only && !file.is_dir) ||
                         (!extensions.is_empty() && (
-                            ext.is_none() ||
-                            (ext.is_some() && !extensions.contains(ext.as_ref().unwrap()))
+                            file.ext.is_none() ||
+                            (file.ext.is_some() && !extensions.contains(file.ext.as_ref().unwrap()))
                         ))
                     {
                         return Ok(None);
                     }
 
-                    Ok(Some(LoopFile {
-                        ext,
-                        stem,
-                        name,
-                        is_file: path.is_file(),
-                        is_dir: path.is_dir(),
-                        path,
-                    }))
+                    file.read_front_matter()?;
+
+                    Ok(Some(file))
                 })
                 .collect::<Result<Vec<Option<LoopFile>>>>()?
                 .into_iter()
@@ -1738,14 +3012,23 @@ impl Parser {
 
         items.sort_unstable_by(|f1, f2| f1.path.cmp(&f2.path));
 
-        if reverse {
-            items.reverse();
+        let mut items = items.into_iter()
+            .map(LoopFile::entry)
+            .collect::<Vec<JsonValue>>();
+
+        // modifiers are applied in the order they are written
+        for m in mods.iter().flatten() {
+            match m {
+                ForFileMod::Reverse => items.reverse(),
+                ForFileMod::Slice(slice) => items = slice.apply(items),
+                ForFileMod::Sort(order) => order.sort(&mut items),
+                ForFileMod::Ext(_)|ForFileMod::Files|ForFileMod::Dirs|
+                    ForFileMod::Recursive(_)|ForFileMod::Exclude(_) => {},
+            }
         }
 
-        let len = items.len();
-
         let has_items = if items.is_empty() {
-            items = vec![ LoopFile::default(), ];
+            items = vec![ JsonValue::Null, ];
             false
         }
         else {
@@ -1753,31 +3036,26 @@ impl Parser {
         };
 
         let alias_cl = alias.clone();
-        for (idx, item) in items.into_iter().enumerate() {
+        for (idx, entry) in items.iter().enumerate() {
             // revert back to start of loop
             self.src_mut().set_coord(for_start);
 
-            let item_str = if let Some(item_str) = item.path.to_str() {
-                item_str.to_owned()
-            }
-            else {
-                "".to_owned()
+            // a batch of entries is placed into the map as an array of paths
+            let item = match entry {
+                JsonValue::Array(batch) => JsonValue::Array(batch.iter()
+                    .map(|e| e["path"].clone())
+                    .collect()),
+                e => e["path"].as_str().unwrap_or("").into(),
             };
 
             let for_output = self.spawn_sealed_internal_parser(|p| {
                 // place value into map
-                p.set_json_value(alias_cl.clone(), item_str.clone().into())?;
+                p.set_json_value(alias_cl.clone(), item)?;
 
                 // setup loop context
                 if !bypass && has_items {
-                    p.loop_context(idx, len)?;
-
-                    p.set_json_value("$loop.entry.path".to_owned(), item_str.clone().into())?;
-                    p.set_json_value("$loop.entry.ext".to_owned(), item.ext.clone().into())?;
-                    p.set_json_value("$loop.entry.stem".to_owned(), item.stem.clone().into())?;
-                    p.set_json_value("$loop.entry.name".to_owned(), item.name.clone().into())?;
-                    p.set_json_value("$loop.entry.is_file".to_owned(), item.is_file.into())?;
-                    p.set_json_value("$loop.entry.is_dir".to_owned(), item.is_dir.into())?;
+                    p.loop_context(idx, &items)?;
+                    p.set_json_value("$loop.entry".to_owned(), entry.clone())?;
                 }
 
                 // parse next until endblock.
@@ -1794,11 +3072,18 @@ impl Parser {
                 Ok(())
             })?;
 
+            // a break tag ends the loop once the rest of the tag is read
+            let stop = matches!(self.loop_control.take(), Some((LoopControl::Break, _)));
+
             if !bypass && has_items {
                 self.output.push_str(&for_output);
             }
 
             if self.trim_or_end() {
+                if stop {
+                    break;
+                }
+
                 continue;
             }
 
@@ -1828,12 +3113,49 @@ impl Parser {
             if !bypass && !has_items {
                 self.output.push_str(&else_output);
             }
+
+            if stop {
+                break;
+            }
         }
 
         Ok(true)
     }
 
-    fn for_item_mods(&mut self, start: Coordinate) -> Result<Option<Vec<ForItemMod>>> {
+    fn sort_order(&mut self, tag: &str, key: Option<String>, start: Coordinate) -> Result<SortOrder> {
+        let mut order = SortOrder {
+            key,
+            natural: false,
+            descending: false,
+        };
+
+        loop {
+            self.src_mut().trim_start();
+            self.unexpected_eof(|| Error::UnterminatedTag(
+                tag.to_owned(),
+                start,
+                self.src().file().to_owned(),
+            ))?;
+
+            if self.src().pos().starts_with(consts::modif::ASC) {
+                self.src_mut().take(consts::modif::ASC.len());
+                order.descending = false;
+            }
+            else if self.src().pos().starts_with(consts::modif::DESC) {
+                self.src_mut().take(consts::modif::DESC.len());
+                order.descending = true;
+            }
+            else if self.src().pos().starts_with(consts::modif::NATURAL) {
+                self.src_mut().take(consts::modif::NATURAL.len());
+                order.natural = true;
+            }
+            else {
+                return Ok(order);
+            }
+        }
+    }
+
+    fn for_item_mods(&mut self, start: Coordinate, bypass: bool) -> Result<Option<Vec<ForItemMod>>> {
         if !self.src().pos().starts_with(consts::block::MODIFIER) {
             return Ok(None);
         }
@@ -1863,6 +3185,80 @@ impl Parser {
                 self.src_mut().take(consts::modif::PATHS.len());
                 mods.push(ForItemMod::Paths);
             }
+            else if let Some(slice) = self.loop_slice(TAG_NAME, start, bypass)? {
+                mods.push(ForItemMod::Slice(slice));
+            }
+            else if self.src().pos().starts_with(consts::modif::SORT_BY) {
+                self.src_mut().take(consts::modif::SORT_BY.len());
+                self.src_mut().trim_start();
+                unexpected_eof(self, start)?;
+
+                if !self.src().pos().starts_with(consts::PATH) {
+                    return Err(self.illegal_character(TAG_NAME));
+                }
+
+                let key = self.path(bypass)?;
+                let order = self.sort_order(TAG_NAME, Some(key), start)?;
+
+                mods.push(ForItemMod::Sort(order));
+            }
+            else if self.src().pos().starts_with(consts::modif::SORT) {
+                self.src_mut().take(consts::modif::SORT.len());
+
+                let order = self.sort_order(TAG_NAME, None, start)?;
+
+                mods.push(ForItemMod::Sort(order));
+            }
+            else if self.src().pos().starts_with(consts::modif::GROUP_BY) {
+                self.src_mut().take(consts::modif::GROUP_BY.len());
+                self.src_mut().trim_start();
+                unexpected_eof(self, start)?;
+
+                if !self.src().pos().starts_with(consts::PATH) {
+                    return Err(self.illegal_character(TAG_NAME));
+                }
+
+                let key = self.path(bypass)?;
+
+                self.src_mut().trim_start();
+                unexpected_eof(self, start)?;
+
+                let format = if self.src().pos().starts_with(consts::modif::DATE) {
+                    self.src_mut().take(consts::modif::DATE.len());
+                    self.src_mut().trim_start();
+                    unexpected_eof(self, start)?;
+
+                    if !self.src().pos().starts_with(consts::PATH) {
+                        return Err(self.illegal_character(TAG_NAME));
+                    }
+
+                    let coord = self.src().coord();
+                    let format = self.path(bypass)?;
+
+                    match DateFormat::parse(&format) {
+                        Some(f) => Some(f),
+                        None if bypass => None,
+                        None => return Err(Error::IllegalArgument(
+                            consts::modif::DATE.to_owned(), format, coord, self.file().to_owned()
+                        )),
+                    }
+                }
+                else {
+                    None
+                };
+
+                mods.push(ForItemMod::Group(GroupBy { key, format, }));
+            }
+            else if self.src().pos().starts_with(consts::modif::WHERE) {
+                self.src_mut().take(consts::modif::WHERE.len());
+
+                // the condition is evaluated against each item once they are
+                // read
+                let coord = self.src().coord();
+                self.condition(TAG_NAME, start, false)?;
+
+                mods.push(ForItemMod::Where(coord));
+            }
             else {
                 return Err(self.illegal_character(TAG_NAME));
             }
@@ -1878,6 +3274,118 @@ impl Parser {
         Ok(Some(mods))
     }
 
+    /// Place the item of a loop into the map, splitting an object entry, or a
+    /// batch of them, into its key and value when a value alias is given.
+    fn set_loop_item(&mut self, alias: &str, value_alias: Option<&str>, item: JsonValue) -> Result<()> {
+        let value_alias = match value_alias {
+            Some(value_alias) => value_alias,
+            None => return self.set_json_value(alias, item),
+        };
+
+        let (key, value) = match &item {
+            JsonValue::Array(batch) => (
+                batch.iter().map(|e| e["key"].clone()).collect(),
+                batch.iter().map(|e| e["value"].clone()).collect(),
+            ),
+            e => (e["key"].clone(), e["value"].clone()),
+        };
+
+        self.set_json_value(alias, key)?;
+        self.set_json_value(value_alias, value)
+    }
+
+    /// Read a bound or step of a range, either an integer or an alias to one.
+    fn range_bound(&mut self, bound: &str, range: &str, coord: Coordinate) -> Result<i64> {
+        if bound.is_empty() {
+            return Err(Error::IllegalArgument(
+                "range".to_owned(), range.to_owned(), coord, self.file().to_owned(),
+            ));
+        }
+
+        if let Ok(n) = bound.parse::<i64>() {
+            return Ok(n);
+        }
+
+        let value = self.enforce_context(|ctx| Ok(ctx.get(bound)?.clone()))?;
+        let n = match &value {
+            JsonValue::Number(n) => n.as_i64()
+                .or_else(|| n.as_f64().filter(|f| f.fract() == 0_f64).map(|f| f as i64)),
+            JsonValue::String(s) => s.trim().parse::<i64>().ok(),
+            _ => None,
+        };
+
+        n.ok_or_else(|| Error::ValueNotNumber(bound.into()))
+    }
+
+    /// Produce the integers from the start of a range to its end, inclusive,
+    /// counting down when the end is less than the start.
+    fn range(&mut self, range: &str, coord: Coordinate) -> Result<Vec<JsonValue>> {
+        let parts = range.split(consts::exp::RANGE).collect::<Vec<&str>>();
+        if parts.len() > 3 {
+            return Err(Error::IllegalArgument(
+                "range".to_owned(), range.to_owned(), coord, self.file().to_owned(),
+            ));
+        }
+
+        let from = self.range_bound(parts[0], range, coord)?;
+        let to = self.range_bound(parts[1], range, coord)?;
+        let step = match parts.get(2) {
+            Some(step) => self.range_bound(step, range, coord)?.unsigned_abs(),
+            None => 1,
+        };
+
+        if step == 0 {
+            return Err(Error::IllegalArgument(
+                "range".to_owned(), range.to_owned(), coord, self.file().to_owned(),
+            ));
+        }
+
+        // the items are produced up front, so their number is limited
+        let len = from.abs_diff(to) / step;
+        if len >= consts::MAX_RANGE_LEN {
+            return Err(Error::IllegalArgument(
+                "range".to_owned(), range.to_owned(), coord, self.file().to_owned(),
+            ));
+        }
+
+        Ok((0..=len)
+            .map(|i| {
+                let offset = (i * step) as i64;
+                if from <= to { from + offset } else { from - offset }
+            })
+            .map(JsonValue::from)
+            .collect())
+    }
+
+    /// Keep the items which meet the condition at `coord` when placed into
+    /// the context at `alias`.
+    fn filter_items(
+        &mut self, alias: &str, value_alias: Option<&str>, items: Vec<JsonValue>,
+        coord: Coordinate, start: Coordinate,
+    ) -> Result<Vec<JsonValue>> {
+        let after = self.src().coord();
+        let mut kept = Vec::new();
+
+        for item in items {
+            self.src_mut().set_coord(coord);
+
+            let mut keep = false;
+            self.spawn_sealed_internal_parser(|p| {
+                p.set_loop_item(alias, value_alias, item.clone())?;
+                keep = p.condition("for-item", start, true)?;
+                Ok(())
+            })?;
+
+            if keep {
+                kept.push(item);
+            }
+        }
+
+        self.src_mut().set_coord(after);
+
+        Ok(kept)
+    }
+
     fn for_item(&mut self, bypass: bool) -> Result<bool> {
         if !self.src().pos().starts_with(consts::block::FOR_ITEM) {
             return Ok(false);
@@ -1903,13 +3411,38 @@ impl Parser {
         self.src_mut().trim_start();
         unexpected_eof_for(self, start)?;
 
+        // a second alias loops through the entries of an object
+        let value_alias = if self.src().pos().starts_with(consts::exp::ENTRY_SEP) {
+            self.src_mut().take(1);
+            self.src_mut().trim_start();
+            unexpected_eof_for(self, start)?;
+
+            let value_alias = self.alias(TAG_NAME)?;
+
+            self.src_mut().trim_start();
+            unexpected_eof_for(self, start)?;
+
+            Some(value_alias)
+        }
+        else {
+            None
+        };
+
         // take "in"
         self.in_keyword(TAG_NAME)?;
         unexpected_eof_for(self, start)?;
 
+        let in_coord = self.src().coord();
         let in_alias = self.alias(TAG_NAME)?;
         self.src_mut().trim_start();
 
+        let is_range = in_alias.contains(consts::exp::RANGE);
+        if is_range && value_alias.is_some() && !bypass {
+            return Err(Error::IllegalArgument(
+                "range".to_owned(), in_alias, in_coord, self.file().to_owned(),
+            ));
+        }
+
         let nullable = if self.src().pos().starts_with(consts::exp::NULLABLE) {
             self.src_mut().take(1);
             true
@@ -1921,7 +3454,7 @@ impl Parser {
         self.src_mut().trim_start();
         unexpected_eof_for(self, start)?;
 
-        let mods = self.for_item_mods(start)?;
+        let mods = self.for_item_mods(start, bypass)?;
         self.src_mut().trim_start();
         unexpected_eof_for(self, start)?;
 
@@ -1937,20 +3470,7 @@ impl Parser {
 
         let for_start = self.src().coord();
 
-        let reverse = mods.as_ref()
-            .map(|m| m.iter()
-                .filter_map(|m| if let ForItemMod::Reverse = m {
-                    Some(())
-                }
-                else {
-                    None
-                })
-                .collect::<Vec<()>>()
-            )
-            .unwrap_or(Vec::new())
-            .len() % 2 != 0;
-
-        let as_paths = !mods
+        let as_paths = !mods.as_ref()
             .map(|m| m.iter()
                 .filter_map(|m| if let ForItemMod::Paths = m {
                     Some(())
@@ -1966,8 +3486,19 @@ impl Parser {
         let mut items = if bypass {
             vec![]
         }
+        else if is_range {
+            self.range(&in_alias, in_coord)?
+        }
         else if let Some(ctx) = self.ctx_mut() {
-            if as_paths {
+            if value_alias.is_some() {
+                if nullable {
+                    ctx.get_entries_opt(in_alias, as_paths)?
+                }
+                else {
+                    ctx.get_entries(in_alias, as_paths)?
+                }
+            }
+            else if as_paths {
                 if nullable {
                     ctx.get_array_opt_as_paths(in_alias)?
                 }
@@ -1986,12 +3517,28 @@ impl Parser {
             vec![]
         };
 
-        if reverse {
-            items.reverse();
+        // modifiers are applied in the order they are written
+        for m in mods.iter().flatten() {
+            match m {
+                ForItemMod::Reverse => items.reverse(),
+                // entries are sorted by key, or by a key of their value
+                ForItemMod::Sort(order) if value_alias.is_some() => SortOrder {
+                    key: Some(match &order.key {
+                        Some(key) if key != "key" => format!("value.{key}"),
+                        _ => "key".to_owned(),
+                    }),
+                    ..order.clone()
+                }.sort(&mut items),
+                ForItemMod::Sort(order) => order.sort(&mut items),
+                ForItemMod::Slice(slice) => items = slice.apply(items),
+                ForItemMod::Group(group) => items = group.apply(items, value_alias.is_some())?,
+                ForItemMod::Where(coord) => items = self.filter_items(
+                    &alias, value_alias.as_deref(), items, *coord, start
+                )?,
+                ForItemMod::Paths => {},
+            }
         }
 
-        let len = items.len();
-
         let has_items = if items.is_empty() {
             items = vec![ JsonValue::String("".to_owned()), ];
             false
@@ -2001,17 +3548,17 @@ impl Parser {
         };
 
         let alias_cl = alias.clone();
-        for (idx, item) in items.into_iter().enumerate() {
+        for (idx, item) in items.iter().enumerate() {
             // revert back to start of loop
             self.src_mut().set_coord(for_start);
 
             let for_output = self.spawn_sealed_internal_parser(|p| {
                 // place value into map
-                p.set_json_value(alias_cl.clone(), item)?;
+                p.set_loop_item(&alias_cl, value_alias.as_deref(), item.clone())?;
 
                 // setup loop context
                 if !bypass && has_items {
-                    p.loop_context(idx, len)?;
+                    p.loop_context(idx, &items)?;
                 }
 
                 // parse next until endblock.
@@ -2028,11 +3575,18 @@ impl Parser {
                 Ok(())
             })?;
 
+            // a break tag ends the loop once the rest of the tag is read
+            let stop = matches!(self.loop_control.take(), Some((LoopControl::Break, _)));
+
             if !bypass && has_items {
                 self.output.push_str(&for_output);
             }
 
             if self.trim_or_end() {
+                if stop {
+                    break;
+                }
+
                 continue;
             }
 
@@ -2062,6 +3616,10 @@ impl Parser {
             if !bypass && !has_items {
                 self.output.push_str(&else_output);
             }
+
+            if stop {
+                break;
+            }
         }
 
         Ok(true)
@@ -2087,7 +3645,7 @@ impl Parser {
         self.src_mut().take(1);
         unexpected_eof(self, start)?;
 
-        let output = self.spawn_sealed_internal_parser(|p| {
+        let output = self.spawn_unescaped_internal_parser(|p| {
             while !p.src().eof() && !p.src().pos().starts_with(consts::block::ENDBLOCK) {
                 p.parse_next(bypass)?;
             }
@@ -2099,7 +3657,7 @@ impl Parser {
 
         if !bypass {
             let s_path = self.path.clone();
-            let new_ctx = JsonContext::read_from_string(&s_path, output, Some(consts::ROOT))?;
+            let new_ctx = JsonContext::read_from_string(&s_path, output, Some(consts::ROOT), Format::Json)?;
 
             if let Some(ctx) = self.ctx_mut() {
                 ctx.merge(s_path, new_ctx)?;
@@ -2155,7 +3713,7 @@ impl Parser {
 
         self.do_trim_start(TAG_NAME, start)?;
 
-        let output = self.spawn_sealed_internal_parser(|p| {
+        let output = self.spawn_unescaped_internal_parser(|p| {
             while !p.src().eof() && !p.src().pos().starts_with(consts::block::ENDBLOCK) {
                 p.parse_next(bypass)?;
             }
@@ -2180,68 +3738,614 @@ impl Parser {
         Ok(true)
     }
 
-    fn unset_item(&mut self) -> Result<bool> {
-        if !self.src().pos().starts_with(consts::block::UNSET_ITEM) {
+    fn loop_control_tag(&mut self, bypass: bool) -> Result<bool> {
+        if !self.src().pos().starts_with(consts::block::LOOP_CONTROL) {
             return Ok(false);
         }
 
-        const TAG_NAME: &str = "unset-item";
+        const TAG_NAME: &str = "loop-control";
 
-        self.src_mut().take(consts::block::UNSET_ITEM.len());
+        let start = self.src().coord();
+
+        self.src_mut().take(consts::block::LOOP_CONTROL.len());
         self.src_mut().trim_start();
+        self.unexpected_eof(|| Error::UnterminatedTag(
+            TAG_NAME.to_owned(),
+            start,
+            self.src().file().to_owned(),
+        ))?;
 
-        fn unexpected_eof(p: &mut Parser, coord: Coordinate) -> Result<()> {
-            p.unexpected_eof(|| Error::UnterminatedTag(
-                TAG_NAME.to_owned(),
-                coord,
-                p.src().file().to_owned()
-            ))
+        let control = if self.src().pos().starts_with(consts::exp::BREAK) {
+            self.src_mut().take(consts::exp::BREAK.len());
+            LoopControl::Break
         }
-
-        let start = self.src().coord();
-        unexpected_eof(self, start)?;
-
-        let alias = self.alias(TAG_NAME)?;
+        else if self.src().pos().starts_with(consts::exp::CONTINUE) {
+            self.src_mut().take(consts::exp::CONTINUE.len());
+            LoopControl::Continue
+        }
+        else {
+            return Err(self.illegal_character(TAG_NAME));
+        };
 
         self.src_mut().trim_start();
-        unexpected_eof(self, start)?;
+        self.unexpected_eof(|| Error::UnterminatedTag(
+            control.tag_name().to_owned(),
+            start,
+            self.src().file().to_owned(),
+        ))?;
+
+        // an optional condition guards the tag
+        let is_true = if self.src().pos().starts_with(consts::block::ENDTAG) {
+            true
+        }
+        else {
+            self.condition(control.tag_name(), start, !bypass)?
+        };
 
         if !self.src().pos().starts_with(consts::block::ENDTAG) {
-            return Err(self.illegal_character(TAG_NAME));
+            return Err(self.illegal_character(control.tag_name()));
         }
 
         self.src_mut().take(1);
 
-        if alias == consts::CONTENT {
-            self.output = String::new();
-            return Ok(true);
+        if !bypass && is_true {
+            self.loop_control = Some((control, start));
         }
 
-        self.remove_value(alias);
-
         Ok(true)
     }
 
-    fn trim_start_tag(&mut self) -> Result<bool> {
-        // if doesn't start with trim character or trim character is not the
-        // final character on the line
-        if !self.src().pos().starts_with(consts::block::TRIM_LF) && (
-            !self.src().pos().starts_with(consts::block::TRIM) ||
-            self.src().pos().len() > 1
-        ) {
-            return Ok(false);
+    /// Read the value given to a macro parameter, either a quoted string or an
+    /// alias.
+    fn macro_arg(&mut self, tag: &str, bypass: bool) -> Result<MacroArg> {
+        if self.src().pos().starts_with(consts::PATH) {
+            Ok(MacroArg::Literal(self.path(bypass)?))
+        }
+        else {
+            Ok(MacroArg::Alias(self.alias(tag)?))
         }
-
-        self.src_mut().take(1);
-        self.src_mut().trim_start_multiline();
-
-        Ok(true)
     }
 
-    fn delete_path(&mut self, bypass: bool) -> Result<bool> {
-        //-{ "some/path/here.txt" }
+    /// Read the names of a macro call or definition and any values given to
+    /// them, up to the end of the tag.
+    fn macro_params(
+        &mut self, tag: &str, start: Coordinate, define: bool, bypass: bool,
+    ) -> Result<Vec<(String, Option<MacroArg>, Coordinate)>> {
+        let mut params = Vec::new();
 
-        if !self.src().pos().starts_with(consts::block::DELETE_PATH) {
+        loop {
+            self.src_mut().trim_start();
+            self.unexpected_eof(|| Error::UnterminatedTag(
+                tag.to_owned(),
+                start,
+                self.src().file().to_owned(),
+            ))?;
+
+            if self.src().pos().starts_with(consts::block::ENDTAG) {
+                break;
+            }
+
+            let coord = self.src().coord();
+            let name = self.alias(tag)?;
+
+            let value = if self.src().pos().starts_with(consts::exp::ASSIGN) {
+                self.src_mut().take(1);
+                Some(self.macro_arg(tag, bypass)?)
+            }
+            // an optional parameter defaults to null
+            else if define && self.src().pos().starts_with(consts::exp::NULLABLE) {
+                self.src_mut().take(1);
+                Some(MacroArg::Null)
+            }
+            else if !define {
+                return Err(self.illegal_character(tag));
+            }
+            else {
+                None
+            };
+
+            params.push((name, value, coord));
+        }
+
+        // take endtag
+        self.src_mut().take(1);
+
+        Ok(params)
+    }
+
+    fn macro_value(&mut self, arg: &MacroArg) -> Result<JsonValue> {
+        match arg {
+            MacroArg::Literal(s) => Ok(s.to_owned().into()),
+            MacroArg::Alias(a) => Ok(self.optional_context(|ctx| Ok(Some(ctx.get(a.as_str())?.clone())))?
+                .unwrap_or(JsonValue::Null)),
+            MacroArg::Null => Ok(JsonValue::Null),
+        }
+    }
+
+    /// Whether a value given to a template parameter is of its type.
+    fn param_is_type(&mut self, name: &str, ty: ParamType, value: &JsonValue) -> Result<bool> {
+        Ok(match (ty, value) {
+            (ParamType::String, JsonValue::String(_)) |
+            (ParamType::Number, JsonValue::Number(_)) |
+            (ParamType::Bool, JsonValue::Bool(_)) |
+            (ParamType::Array, JsonValue::Array(_)) |
+            (ParamType::Object, JsonValue::Object(_)) => true,
+            (ParamType::Path, JsonValue::String(_)) => self.enforce_context(|ctx| ctx.get_path(name))?
+                .exists(),
+            _ => false,
+        })
+    }
+
+    /// Read the parameters declared by a template, placing any defaults into
+    /// the context and checking the value given to each against its type.
+    fn template_params(&mut self, tag: &str, start: Coordinate, bypass: bool) -> Result<()> {
+        let mut params = Vec::new();
+
+        loop {
+            self.src_mut().trim_start();
+            self.unexpected_eof(|| Error::UnterminatedTag(
+                tag.to_owned(),
+                start,
+                self.src().file().to_owned(),
+            ))?;
+
+            if self.src().pos().starts_with(consts::block::ENDTAG) {
+                break;
+            }
+
+            let coord = self.src().coord();
+            let name = self.alias(tag)?;
+
+            let ty = if self.src().pos().starts_with(consts::exp::PARAM_TYPE) {
+                self.src_mut().take(1);
+                let ty = self.alias(tag)?;
+                match ParamType::from_name(&ty) {
+                    Some(ty) => Some(ty),
+                    None => return Err(Error::IllegalArgument(
+                        consts::exp::PARAMS.to_owned(), ty, coord, self.file().to_owned(),
+                    )),
+                }
+            }
+            else {
+                None
+            };
+
+            let default = if self.src().pos().starts_with(consts::exp::ASSIGN) {
+                self.src_mut().take(1);
+                Some(self.macro_arg(tag, bypass)?)
+            }
+            // an optional parameter defaults to null
+            else if self.src().pos().starts_with(consts::exp::NULLABLE) {
+                self.src_mut().take(1);
+                Some(MacroArg::Null)
+            }
+            else {
+                None
+            };
+
+            params.push((name, ty, default));
+        }
+
+        // take endtag
+        self.src_mut().take(1);
+
+        if bypass {
+            return Ok(());
+        }
+
+        for (name, ty, default) in params {
+            let mut value = self.macro_value(&MacroArg::Alias(name.to_owned()))?;
+
+            if value.is_null() {
+                match default {
+                    Some(MacroArg::Null) => continue,
+                    Some(MacroArg::Literal(s)) if ty.is_some_and(|t| t.is_json()) => {
+                        value = serde_json::from_str(&s).unwrap_or(JsonValue::String(s));
+                    },
+                    Some(default) => value = self.macro_value(&default)?,
+                    None => return Err(Error::MissingTemplateParameter(
+                        name, self.file().to_owned(), start, self.file().to_owned(),
+                    )),
+                }
+
+                self.set_json_value(name.as_str(), value.clone())?;
+            }
+
+            if let Some(ty) = ty {
+                if !self.param_is_type(&name, ty, &value)? {
+                    return Err(Error::TemplateParameterType(
+                        name,
+                        ty.name().to_owned(),
+                        self.file().to_owned(),
+                        start,
+                        self.file().to_owned(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a block without effect or output, leaving the source after it.
+    fn skip_block(&mut self, tag: &str, start: Coordinate) -> Result<()> {
+        self.spawn_sealed_internal_parser(|p| {
+            while !p.src().eof() && !p.src().pos().starts_with(consts::block::ENDBLOCK) {
+                p.parse_next(true)?;
+            }
+
+            p.unexpected_eof(|| Error::UnterminatedTag(
+                tag.to_owned(),
+                start,
+                p.src().file().to_owned(),
+            ))?;
+
+            p.src_mut().take(1);
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    fn macro_tag(&mut self, bypass: bool) -> Result<bool> {
+        if !self.src().pos().starts_with(consts::block::MACRO) {
+            return Ok(false);
+        }
+
+        const TAG_NAME: &str = "macro";
+
+        let start = self.src().coord();
+
+        self.src_mut().take(consts::block::MACRO.len());
+        self.src_mut().trim_start();
+        self.unexpected_eof(|| Error::UnterminatedTag(
+            TAG_NAME.to_owned(),
+            start,
+            self.src().file().to_owned(),
+        ))?;
+
+        let name = self.alias(TAG_NAME)?;
+
+        if name == consts::exp::BLOCK {
+            self.named_block(TAG_NAME, start, bypass)?;
+            return Ok(true);
+        }
+
+        if name == consts::exp::SUPER {
+            self.src_mut().trim_start();
+            if !self.src().pos().starts_with(consts::block::ENDTAG) {
+                return Err(self.illegal_character(TAG_NAME));
+            }
+
+            self.src_mut().take(1);
+
+            if !bypass {
+                if let Some(output) = &self.super_block {
+                    self.output.push_str(output);
+                }
+            }
+
+            return Ok(true);
+        }
+
+        if name == consts::exp::PARAMS {
+            self.template_params(TAG_NAME, start, bypass)?;
+            return Ok(true);
+        }
+
+        // the definitions of an imported file are read though it is bypassed
+        let bypass_definitions = bypass && self.imports.is_empty();
+
+        if name == consts::exp::IMPORT {
+            self.src_mut().trim_start();
+            let path = self.pathlike(TAG_NAME, bypass_definitions)?;
+            let path = self.normalize_path(path);
+
+            self.src_mut().trim_start();
+            if !self.src().pos().starts_with(consts::block::ENDTAG) {
+                return Err(self.illegal_character(TAG_NAME));
+            }
+
+            self.src_mut().take(1);
+
+            if !bypass_definitions {
+                let mut imports = self.imports.clone();
+                imports.push(self.path.clone());
+
+                if imports.contains(&path) {
+                    return Err(Error::CircularImport(path, start, self.file().to_owned()));
+                }
+
+                // the imported file is parsed only for its definitions, so
+                // that nothing is output, written or read
+                let mut importer = Self::new_internal(&path, None, self.context.clone(), self.escape)?;
+                importer.build = self.build.clone();
+                importer.context_schema = self.context_schema.clone();
+                importer.imports = imports;
+                while !importer.src().eof() {
+                    importer.parse_next(true)?;
+                }
+                Rc::make_mut(&mut self.macros).extend(Rc::try_unwrap(importer.macros).unwrap_or_else(|m| (*m).clone()));
+            }
+
+            return Ok(true);
+        }
+
+        if name == consts::exp::MACRO {
+            self.src_mut().trim_start();
+            let name_coord = self.src().coord();
+            let name = self.alias(TAG_NAME)?;
+
+            // names which the tag reads as keywords could never be called
+            let reserved = [
+                consts::exp::MACRO, consts::exp::IMPORT, consts::exp::BLOCK,
+                consts::exp::SUPER, consts::exp::PARAMS,
+            ];
+
+            if reserved.contains(&name.as_str()) {
+                return Err(Error::ReservedMacroName(name, name_coord, self.file().to_owned()));
+            }
+
+            let params = self.macro_params(TAG_NAME, start, true, bypass_definitions)?
+                .into_iter()
+                .map(|(name, default, _)| MacroParam { name, default, })
+                .collect();
+
+            self.do_trim_start(TAG_NAME, start)?;
+
+            // the body is parsed when the macro is called
+            let source = self.src().clone();
+            self.skip_block(TAG_NAME, start)?;
+
+            if !bypass_definitions {
+                Rc::make_mut(&mut self.macros).insert(name, Macro { params, source, });
+            }
+
+            return Ok(true);
+        }
+
+        let args = self.macro_params(TAG_NAME, start, false, bypass)?;
+
+        // the block of a call is placed into the macro as its content
+        let content = if self.trim_or_end() {
+            None
+        }
+        else {
+            Some(self.spawn_sealed_internal_parser(|p| {
+                while !p.src().eof() && !p.src().pos().starts_with(consts::block::ENDBLOCK) {
+                    p.parse_next(bypass)?;
+                }
+
+                p.unexpected_eof(|| Error::UnterminatedTag(
+                    TAG_NAME.to_owned(),
+                    start,
+                    p.src().file().to_owned(),
+                ))?;
+
+                p.src_mut().take(1);
+
+                Ok(())
+            })?)
+        };
+
+        if bypass {
+            return Ok(true);
+        }
+
+        let m = match self.macros.get(&name) {
+            Some(m) => m.clone(),
+            None => return Err(Error::UnknownMacro(name, start, self.file().to_owned())),
+        };
+
+        let mut values = Vec::new();
+        for (arg, value, coord) in &args {
+            if !m.params.iter().any(|p| &p.name == arg) {
+                return Err(Error::UnknownParameter(
+                    name, arg.to_owned(), *coord, self.file().to_owned(),
+                ));
+            }
+
+            values.push((arg.to_owned(), self.macro_value(value.as_ref().unwrap())?));
+        }
+
+        for param in &m.params {
+            if args.iter().any(|(arg, _, _)| arg == &param.name) {
+                continue;
+            }
+
+            match &param.default {
+                Some(default) => values.push((param.name.to_owned(), self.macro_value(default)?)),
+                None => return Err(Error::MissingParameter(
+                    name, param.name.to_owned(), start, self.file().to_owned(),
+                )),
+            }
+        }
+
+        if self.macro_depth >= consts::MAX_MACRO_DEPTH {
+            return Err(Error::MacroDepth(
+                name, consts::MAX_MACRO_DEPTH, start, self.file().to_owned(),
+            ));
+        }
+
+        let mut p = self.source_parser(m.source);
+        p.macro_depth = self.macro_depth + 1;
+
+        match content {
+            Some(content) => p.set_json_value(consts::CONTENT, content.into())?,
+            None => p.remove_value(consts::CONTENT),
+        }
+
+        for (param, value) in values {
+            p.set_json_value(param, value)?;
+        }
+
+        p.parse_body()?;
+        self.output.push_str(&p.output);
+
+        Ok(true)
+    }
+
+    /// Create a parser for a body stored from another source, such as that of
+    /// a macro or block, with a sealed copy of this parser's context.
+    fn source_parser(&self, source: Source) -> Self {
+        Self {
+            path: source.file().to_owned(),
+            context: self.context.clone(),
+            extends: None,
+            can_extend: false,
+            source,
+            output: String::new(),
+            escape: self.escape,
+            build: self.build.clone(),
+            loop_control: None,
+            macros: self.macros.clone(),
+            macro_depth: self.macro_depth,
+            blocks: self.blocks.clone(),
+            super_block: None,
+            context_schema: self.context_schema.clone(),
+            imports: Vec::new(),
+        }
+    }
+
+    /// Parse a stored body until its endblock.
+    fn parse_body(&mut self) -> Result<()> {
+        while !self.src().eof() && !self.src().pos().starts_with(consts::block::ENDBLOCK) {
+            self.parse_next(false)?;
+        }
+
+        if let Some((control, coord)) = self.loop_control {
+            return Err(Error::IllegalLoopControl(
+                control.tag_name().to_owned(), coord, self.src().file().to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Define or place a named block. Within a template which extends another
+    /// the block overrides that of the same name, and otherwise it is placed
+    /// into the output, replaced by any override.
+    fn named_block(&mut self, tag: &str, start: Coordinate, bypass: bool) -> Result<()> {
+        self.src_mut().trim_start();
+        let name = self.alias(tag)?;
+
+        self.src_mut().trim_start();
+        if !self.src().pos().starts_with(consts::block::ENDTAG) {
+            return Err(self.illegal_character(tag));
+        }
+
+        self.src_mut().take(1);
+        self.do_trim_start(tag, start)?;
+
+        if self.extends.is_some() {
+            let source = self.src().clone();
+            self.skip_block(tag, start)?;
+
+            if !bypass {
+                Rc::make_mut(&mut self.blocks).entry(name).or_default().push(source);
+            }
+
+            return Ok(());
+        }
+
+        let mut output = self.spawn_sealed_internal_parser(|p| {
+            while !p.src().eof() && !p.src().pos().starts_with(consts::block::ENDBLOCK) {
+                p.parse_next(bypass)?;
+            }
+
+            p.unexpected_eof(|| Error::UnterminatedTag(
+                tag.to_owned(),
+                start,
+                p.src().file().to_owned(),
+            ))?;
+
+            p.src_mut().take(1);
+
+            Ok(())
+        })?;
+
+        if bypass {
+            return Ok(());
+        }
+
+        // each override is given the output of the one it overrides, starting
+        // from this block
+        let overrides = self.blocks.get(&name).cloned().unwrap_or_default();
+        for source in overrides.into_iter().rev() {
+            let mut p = self.source_parser(source);
+            p.super_block = Some(output.into());
+            p.parse_body()?;
+            output = p.output;
+        }
+
+        self.output.push_str(&output);
+
+        Ok(())
+    }
+
+    fn unset_item(&mut self) -> Result<bool> {
+        if !self.src().pos().starts_with(consts::block::UNSET_ITEM) {
+            return Ok(false);
+        }
+
+        const TAG_NAME: &str = "unset-item";
+
+        self.src_mut().take(consts::block::UNSET_ITEM.len());
+        self.src_mut().trim_start();
+
+        fn unexpected_eof(p: &mut Parser, coord: Coordinate) -> Result<()> {
+            p.unexpected_eof(|| Error::UnterminatedTag(
+                TAG_NAME.to_owned(),
+                coord,
+                p.src().file().to_owned()
+            ))
+        }
+
+        let start = self.src().coord();
+        unexpected_eof(self, start)?;
+
+        let alias = self.alias(TAG_NAME)?;
+
+        self.src_mut().trim_start();
+        unexpected_eof(self, start)?;
+
+        if !self.src().pos().starts_with(consts::block::ENDTAG) {
+            return Err(self.illegal_character(TAG_NAME));
+        }
+
+        self.src_mut().take(1);
+
+        if alias == consts::CONTENT {
+            self.output = String::new();
+            return Ok(true);
+        }
+
+        self.remove_value(alias);
+
+        Ok(true)
+    }
+
+    fn trim_start_tag(&mut self) -> Result<bool> {
+        // if doesn't start with trim character or trim character is not the
+        // final character on the line
+        if !self.src().pos().starts_with(consts::block::TRIM_LF) && (
+            !self.src().pos().starts_with(consts::block::TRIM) ||
+            self.src().pos().len() > 1
+        ) {
+            return Ok(false);
+        }
+
+        self.src_mut().take(1);
+        self.src_mut().trim_start_multiline();
+
+        Ok(true)
+    }
+
+    fn delete_path(&mut self, bypass: bool) -> Result<bool> {
+        //-{ "some/path/here.txt" }
+
+        if !self.src().pos().starts_with(consts::block::DELETE_PATH) {
             return Ok(false);
         }
 
@@ -2446,6 +4550,21 @@ impl Parser {
     }
 
     fn parse_next(&mut self, bypass: bool) -> Result<()> {
+        // after a break or continue tag the rest of the iteration is read
+        // without effect or output
+        let skipping = self.loop_control.is_some();
+        let len = self.output.len();
+
+        self.parse_next_internal(bypass || skipping)?;
+
+        if skipping {
+            self.output.truncate(len);
+        }
+
+        Ok(())
+    }
+
+    fn parse_next_internal(&mut self, bypass: bool) -> Result<()> {
         // escaped trim_lf: \\<newline>
         if self.src().pos().starts_with(consts::block::esc::TRIM_LF) {
             self.src_mut().take(1);
@@ -2475,7 +4594,9 @@ impl Parser {
             self.src().pos().starts_with(consts::block::esc::UNSET_ITEM) ||
             self.src().pos().starts_with(consts::block::esc::DELETE_PATH) ||
             self.src().pos().starts_with(consts::block::esc::COPY_PATH) ||
-            self.src().pos().starts_with(consts::block::esc::WRITE_CONTENT)
+            self.src().pos().starts_with(consts::block::esc::WRITE_CONTENT) ||
+            self.src().pos().starts_with(consts::block::esc::LOOP_CONTROL) ||
+            self.src().pos().starts_with(consts::block::esc::MACRO)
         {
             self.src_mut().take(1);
             let taken = self.src_mut().take(2).unwrap();
@@ -2514,7 +4635,11 @@ impl Parser {
             // is copy-path
             self.copy_path(bypass)? ||
             // is write-content
-            self.write_content(bypass)?
+            self.write_content(bypass)? ||
+            // is break or continue
+            self.loop_control_tag(bypass)? ||
+            // is macro definition, import, or call
+            self.macro_tag(bypass)?
         {
             // no action required
         }
@@ -2543,12 +4668,23 @@ impl Parser {
             self.parse_next(false)?;
         }
 
-        if let Some(extends) = self.extends.to_owned() {
+        if let Some((control, coord)) = self.loop_control {
+            return Err(Error::IllegalLoopControl(
+                control.tag_name().to_owned(), coord, self.src().file().to_owned(),
+            ));
+        }
+
+        if let Some((extends, start)) = self.extends.to_owned() {
             if !self.output.is_empty() {
                 let orig_output = std::mem::take(&mut self.output);
                 self.set_json_value(consts::CONTENT, orig_output.into())?;
             }
-            let output = self.spawn_parser(extends, |p| p.parse())?;
+            // the blocks of this template override those it extends
+            let blocks = std::mem::take(&mut self.blocks);
+            let output = self.spawn_parser(&extends, |p| {
+                p.blocks = blocks;
+                p.parse()
+            }).map_err(|e| self.at_include_site(&extends, start, e))?;
             self.output.push_str(&output);
         }
 
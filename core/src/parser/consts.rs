@@ -38,3 +38,21 @@ const CONTENT: &str = "$content";
 pub(crate)
 const ROOT: &str = "$root";
 
+pub(crate)
+const BUILD: &str = "$build";
+
+/// The deepest macros may call each other, or themselves.
+pub(crate)
+const MAX_MACRO_DEPTH: usize = 64;
+
+/// The most integers a range may produce.
+pub(crate)
+const MAX_RANGE_LEN: u64 = 1_000_000;
+
+/// The longest value the repeat and pad modifiers may produce.
+pub(crate)
+const MAX_MODIFIER_LEN: usize = 1 << 24;
+
+/// The most decimal places a number may be written with.
+pub(crate)
+const MAX_PLACES: usize = 1_000;
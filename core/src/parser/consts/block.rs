@@ -62,6 +62,12 @@ const COPY_PATH: &str = "~{";
 pub(crate)
 const WRITE_CONTENT: &str = "^{";
 
+pub(crate)
+const LOOP_CONTROL: &str = "!{";
+
+pub(crate)
+const MACRO: &str = "?{";
+
 pub(crate)
 const ENDTAG: char = '}';
 
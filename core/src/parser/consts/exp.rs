@@ -52,3 +52,54 @@ const AND: &str = "&&";
 
 pub(crate)
 const OR: &str = "||";
+
+pub(crate)
+const ENTRY_SEP: char = ',';
+
+pub(crate)
+const RANGE: &str = "..";
+
+pub(crate)
+const BREAK: &str = "break";
+
+pub(crate)
+const CONTINUE: &str = "continue";
+
+pub(crate)
+const MACRO: &str = "macro";
+
+pub(crate)
+const IMPORT: &str = "import";
+
+pub(crate)
+const ASSIGN: char = '=';
+
+pub(crate)
+const BLOCK: &str = "block";
+
+pub(crate)
+const SUPER: &str = "super";
+
+pub(crate)
+const PARAMS: &str = "params";
+
+pub(crate)
+const PARAM_TYPE: char = ':';
+
+pub(crate)
+const TYPE_STRING: &str = "string";
+
+pub(crate)
+const TYPE_NUMBER: &str = "number";
+
+pub(crate)
+const TYPE_BOOL: &str = "bool";
+
+pub(crate)
+const TYPE_ARRAY: &str = "array";
+
+pub(crate)
+const TYPE_OBJECT: &str = "object";
+
+pub(crate)
+const TYPE_PATH: &str = "path";
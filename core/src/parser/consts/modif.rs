@@ -26,6 +26,28 @@ const FILES: &str = "files";
 pub(crate)
 const DIRS: &str = "dirs";
 
+pub(crate)
+const RECURSIVE: &str = "recursive";
+
+pub(crate)
+const EXCLUDE: &str = "exclude";
+
+pub(crate)
+const MTIME: &str = "mtime";
+
+pub(crate)
+const CTIME: &str = "ctime";
+
+pub(crate)
+const SIZE: &str = "size";
+
+/// Must be checked before name.
+pub(crate)
+const NAME_NATURAL: &str = "name-natural";
+
+pub(crate)
+const NAME: &str = "name";
+
 pub(crate)
 const FILENAME: &str = "filename";
 
@@ -61,3 +83,130 @@ const SPLIT: &str = "split";
 
 pub(crate)
 const JSON: &str = "json";
+
+pub(crate)
+const HTML: &str = "html";
+
+pub(crate)
+const ATTR: &str = "attr";
+
+pub(crate)
+const URL: &str = "url";
+
+pub(crate)
+const JS: &str = "js";
+
+pub(crate)
+const CSV: &str = "csv";
+
+pub(crate)
+const SH: &str = "sh";
+
+pub(crate)
+const TRUNCATE: &str = "truncate";
+
+pub(crate)
+const CAPITALIZE: &str = "capitalize";
+
+pub(crate)
+const TITLE: &str = "title";
+
+pub(crate)
+const SLUGIFY: &str = "slugify";
+
+pub(crate)
+const PAD_LEFT: &str = "pad-left";
+
+pub(crate)
+const PAD_RIGHT: &str = "pad-right";
+
+pub(crate)
+const SUBSTRING: &str = "substring";
+
+pub(crate)
+const REPEAT: &str = "repeat";
+
+pub(crate)
+const STRIP_PREFIX: &str = "strip-prefix";
+
+pub(crate)
+const STRIP_SUFFIX: &str = "strip-suffix";
+
+pub(crate)
+const WORDCOUNT: &str = "wordcount";
+
+pub(crate)
+const ESCAPE_NEWLINES: &str = "escape-newlines";
+
+pub(crate)
+const FIXED: &str = "fixed";
+
+pub(crate)
+const THOUSANDS: &str = "thousands";
+
+pub(crate)
+const PERCENT: &str = "percent";
+
+pub(crate)
+const BYTES: &str = "bytes";
+
+pub(crate)
+const DATE: &str = "date";
+
+pub(crate)
+const JOIN: &str = "join";
+
+pub(crate)
+const LENGTH: &str = "length";
+
+pub(crate)
+const FIRST: &str = "first";
+
+pub(crate)
+const LAST: &str = "last";
+
+pub(crate)
+const NTH: &str = "nth";
+
+pub(crate)
+const SLICE: &str = "slice";
+
+// must be checked before sort, which shares a prefix
+pub(crate)
+const SORT_BY: &str = "sort-by";
+
+pub(crate)
+const SORT: &str = "sort";
+
+pub(crate)
+const ASC: &str = "asc";
+
+pub(crate)
+const DESC: &str = "desc";
+
+pub(crate)
+const NATURAL: &str = "natural";
+
+pub(crate)
+const WHERE: &str = "where";
+
+pub(crate)
+const GROUP_BY: &str = "group-by";
+
+pub(crate)
+const LIMIT: &str = "limit";
+
+pub(crate)
+const OFFSET: &str = "offset";
+
+pub(crate)
+const BATCH: &str = "batch";
+
+pub(crate)
+const SCHEMA: &str = "schema";
+
+pub(crate)
+const FORMAT: &str = "format";
+
+pub(crate)
+const MERGE: &str = "merge";
@@ -0,0 +1,328 @@
+//! JSON Schema validation for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use {
+    crate::{
+        context::JsonContext,
+        error::{
+            Error,
+            Result,
+        },
+        file::read_file,
+    },
+    serde_json::Value as JsonValue,
+    std::path::Path,
+};
+
+/// A violation of a schema, as the json pointer to the offending value and a
+/// message.
+pub(crate)
+type Violation = (String, String);
+
+/// A JSON Schema, supporting the subset of draft-07 which describes types,
+/// enumerations, lengths, bounds, properties, items, local references and the
+/// combining keywords.
+#[derive(Clone, Debug)]
+pub(crate)
+struct Schema {
+    root: JsonValue,
+}
+
+impl Schema {
+    pub(crate)
+    fn read<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>
+    {
+        let source = read_file(&path)?;
+        let root = JsonContext::parse_json(&path, source)?;
+        Ok(Self { root, })
+    }
+
+    /// Validate a document, returning an error listing every violation.
+    pub(crate)
+    fn check<P>(&self, doc: &JsonValue, path: P) -> Result<()>
+    where
+        P: AsRef<Path>
+    {
+        let violations = self.validate(doc);
+
+        if violations.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(Error::SchemaViolation(violations, path.as_ref().into()))
+        }
+    }
+
+    pub(crate)
+    fn validate(&self, doc: &JsonValue) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        self.validate_at(&self.root, doc, "", &[], &mut violations);
+        violations
+    }
+
+    fn is_valid(&self, schema: &JsonValue, value: &JsonValue, refs: &[&str]) -> bool {
+        let mut violations = Vec::new();
+        self.validate_at(schema, value, "", refs, &mut violations);
+        violations.is_empty()
+    }
+
+    /// Validate a value, where `refs` are the references followed since the
+    /// value was reached, so that a reference back to one of them is reported
+    /// rather than followed forever.
+    fn validate_at(
+        &self, schema: &JsonValue, value: &JsonValue, pointer: &str, refs: &[&str],
+        out: &mut Vec<Violation>,
+    ) {
+        let mut violation = |msg: String| out.push((pointer.to_owned(), msg));
+
+        let schema = match schema {
+            JsonValue::Bool(true) => return,
+            JsonValue::Bool(false) => return violation("is not allowed".to_owned()),
+            JsonValue::Object(schema) => schema,
+            _ => return,
+        };
+
+        // sibling keywords of a reference are ignored
+        if let Some(JsonValue::String(r)) = schema.get("$ref") {
+            if refs.contains(&r.as_str()) {
+                return violation(format!("has a circular reference {r:?}"));
+            }
+
+            match r.strip_prefix('#').and_then(|p| self.root.pointer(p)) {
+                Some(schema) => {
+                    let mut refs = refs.to_vec();
+                    refs.push(r);
+                    self.validate_at(schema, value, pointer, &refs, out);
+                },
+                None => violation(format!("references unknown schema {r:?}")),
+            }
+
+            return;
+        }
+
+        if let Some(ty) = schema.get("type") {
+            let types = match ty {
+                JsonValue::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+                _ => ty.as_str().into_iter().collect::<Vec<&str>>(),
+            };
+
+            if !types.iter().any(|t| is_type(value, t)) {
+                violation(format!("must be of type {}", types.join(" or ")));
+            }
+        }
+
+        if let Some(JsonValue::Array(values)) = schema.get("enum") {
+            if !values.contains(value) {
+                violation("must be one of the allowed values".to_owned());
+            }
+        }
+
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                violation(format!("must equal {constant}"));
+            }
+        }
+
+        match value {
+            JsonValue::String(s) => {
+                let len = s.chars().count() as u64;
+
+                if let Some(min) = schema.get("minLength").and_then(|n| n.as_u64()) {
+                    if len < min {
+                        violation(format!("must have a length of at least {min}"));
+                    }
+                }
+
+                if let Some(max) = schema.get("maxLength").and_then(|n| n.as_u64()) {
+                    if len > max {
+                        violation(format!("must have a length of at most {max}"));
+                    }
+                }
+            },
+            JsonValue::Number(n) => {
+                let n = n.as_f64().unwrap_or_default();
+                let bound = |key: &str| schema.get(key).and_then(|b| b.as_f64());
+
+                if let Some(min) = bound("minimum") {
+                    if n < min {
+                        violation(format!("must be at least {min}"));
+                    }
+                }
+
+                if let Some(max) = bound("maximum") {
+                    if n > max {
+                        violation(format!("must be at most {max}"));
+                    }
+                }
+
+                if let Some(min) = bound("exclusiveMinimum") {
+                    if n <= min {
+                        violation(format!("must be greater than {min}"));
+                    }
+                }
+
+                if let Some(max) = bound("exclusiveMaximum") {
+                    if n >= max {
+                        violation(format!("must be less than {max}"));
+                    }
+                }
+
+                if let Some(of) = bound("multipleOf") {
+                    if of > 0.0 && (n / of).fract() != 0.0 {
+                        violation(format!("must be a multiple of {of}"));
+                    }
+                }
+            },
+            JsonValue::Array(items) => {
+                let len = items.len() as u64;
+
+                if let Some(min) = schema.get("minItems").and_then(|n| n.as_u64()) {
+                    if len < min {
+                        violation(format!("must have a length of at least {min}"));
+                    }
+                }
+
+                if let Some(max) = schema.get("maxItems").and_then(|n| n.as_u64()) {
+                    if len > max {
+                        violation(format!("must have a length of at most {max}"));
+                    }
+                }
+
+                if let Some(JsonValue::Bool(true)) = schema.get("uniqueItems") {
+                    if items.iter().enumerate().any(|(i, item)| items[i + 1..].contains(item)) {
+                        violation("must have unique items".to_owned());
+                    }
+                }
+
+                if let Some(contains) = schema.get("contains") {
+                    if !items.iter().any(|item| self.is_valid(contains, item, &[])) {
+                        violation("must contain a matching item".to_owned());
+                    }
+                }
+
+                // items given as an array describe each position, with any
+                // after them described by additionalItems
+                let (tuple, rest) = match schema.get("items") {
+                    Some(JsonValue::Array(tuple)) => (tuple.as_slice(), schema.get("additionalItems")),
+                    items => (&[][..], items),
+                };
+
+                for (idx, item) in items.iter().enumerate() {
+                    let item_schema = tuple.get(idx).or(if idx >= tuple.len() { rest } else { None });
+
+                    if let Some(item_schema) = item_schema {
+                        self.validate_at(item_schema, item, &format!("{pointer}/{idx}"), &[], out);
+                    }
+                }
+            },
+            JsonValue::Object(map) => {
+                let len = map.len() as u64;
+
+                if let Some(min) = schema.get("minProperties").and_then(|n| n.as_u64()) {
+                    if len < min {
+                        violation(format!("must have a property count of at least {min}"));
+                    }
+                }
+
+                if let Some(max) = schema.get("maxProperties").and_then(|n| n.as_u64()) {
+                    if len > max {
+                        violation(format!("must have a property count of at most {max}"));
+                    }
+                }
+
+                if let Some(JsonValue::Array(required)) = schema.get("required") {
+                    for key in required.iter().filter_map(|k| k.as_str()) {
+                        if !map.contains_key(key) {
+                            out.push((format!("{pointer}/{}", escape(key)), "is required".to_owned()));
+                        }
+                    }
+                }
+
+                let properties = schema.get("properties").and_then(|p| p.as_object());
+
+                for (key, item) in map {
+                    let item_pointer = format!("{pointer}/{}", escape(key));
+
+                    match properties.and_then(|p| p.get(key)) {
+                        Some(item_schema) => self.validate_at(item_schema, item, &item_pointer, &[], out),
+                        None => if let Some(additional) = schema.get("additionalProperties") {
+                            self.validate_at(additional, item, &item_pointer, &[], out);
+                        },
+                    }
+                }
+            },
+            _ => {},
+        }
+
+        if let Some(JsonValue::Array(all)) = schema.get("allOf") {
+            for schema in all {
+                self.validate_at(schema, value, pointer, refs, out);
+            }
+        }
+
+        if let Some(JsonValue::Array(any)) = schema.get("anyOf") {
+            if !any.iter().any(|schema| self.is_valid(schema, value, refs)) {
+                out.push((pointer.to_owned(), "must match at least one schema".to_owned()));
+            }
+        }
+
+        if let Some(JsonValue::Array(one)) = schema.get("oneOf") {
+            if one.iter().filter(|schema| self.is_valid(schema, value, refs)).count() != 1 {
+                out.push((pointer.to_owned(), "must match exactly one schema".to_owned()));
+            }
+        }
+
+        if let Some(not) = schema.get("not") {
+            if self.is_valid(not, value, refs) {
+                out.push((pointer.to_owned(), "must not match the schema".to_owned()));
+            }
+        }
+
+        if let Some(cond) = schema.get("if") {
+            let branch = if self.is_valid(cond, value, refs) {
+                schema.get("then")
+            }
+            else {
+                schema.get("else")
+            };
+
+            if let Some(branch) = branch {
+                self.validate_at(branch, value, pointer, refs, out);
+            }
+        }
+    }
+}
+
+fn is_type(value: &JsonValue, ty: &str) -> bool {
+    match (ty, value) {
+        ("null", JsonValue::Null) |
+        ("boolean", JsonValue::Bool(_)) |
+        ("number", JsonValue::Number(_)) |
+        ("string", JsonValue::String(_)) |
+        ("array", JsonValue::Array(_)) |
+        ("object", JsonValue::Object(_)) => true,
+        ("integer", JsonValue::Number(n)) => n.is_i64() || n.is_u64() ||
+            n.as_f64().is_some_and(|n| n.fract() == 0.0),
+        _ => false,
+    }
+}
+
+/// Escape a key as a segment of a json pointer.
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
@@ -0,0 +1,159 @@
+//! Ordering of json values for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use {
+    crate::date,
+    serde_json::Value as JsonValue,
+    std::cmp::Ordering,
+};
+
+/// How the items of a loop are sorted.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate)
+struct SortOrder {
+    /// The dot-separated key of each item to sort by, the item itself being
+    /// used when absent.
+    pub(crate) key: Option<String>,
+    /// Whether digits within strings are compared by their numeric value.
+    pub(crate) natural: bool,
+    /// Whether the items are sorted from greatest to least.
+    pub(crate) descending: bool,
+}
+
+impl SortOrder {
+    /// Stably sort items, placing those without a value last.
+    pub(crate)
+    fn sort(&self, items: &mut [JsonValue]) {
+        items.sort_by(|a, b| {
+            let a = self.key(a);
+            let b = self.key(b);
+
+            match (a.is_null(), b.is_null()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) if self.descending => compare(b, a, self.natural),
+                (false, false) => compare(a, b, self.natural),
+            }
+        });
+    }
+
+    fn key<'a>(&self, item: &'a JsonValue) -> &'a JsonValue {
+        match &self.key {
+            Some(key) => lookup(item, key),
+            None => item,
+        }
+    }
+}
+
+/// Find the value at a dot-separated key of an item, `null` when absent.
+pub(crate)
+fn lookup<'a>(item: &'a JsonValue, key: &str) -> &'a JsonValue {
+    let mut value = item;
+    for seg in key.split('.') {
+        value = &value[seg];
+    }
+
+    value
+}
+
+/// Rank the types of json values so that differing types are grouped.
+fn rank(value: &JsonValue) -> u8 {
+    match value {
+        JsonValue::Null => 0,
+        JsonValue::Bool(_) => 1,
+        JsonValue::Number(_) => 2,
+        JsonValue::String(_) => 3,
+        JsonValue::Array(_) => 4,
+        JsonValue::Object(_) => 5,
+    }
+}
+
+/// Compare two json values, numbers numerically and strings in natural order
+/// or with dates before every other string.
+pub(crate)
+fn compare(a: &JsonValue, b: &JsonValue, natural: bool) -> Ordering {
+    match (a, b) {
+        (JsonValue::Bool(a), JsonValue::Bool(b)) => a.cmp(b),
+        (JsonValue::Number(a), JsonValue::Number(b)) => if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+            a.cmp(&b)
+        }
+        else if let (Some(a), Some(b)) = (a.as_u64(), b.as_u64()) {
+            a.cmp(&b)
+        }
+        else {
+            a.as_f64().unwrap_or(0_f64).total_cmp(&b.as_f64().unwrap_or(0_f64))
+        },
+        (JsonValue::String(a), JsonValue::String(b)) if natural => natural_compare(a, b),
+        (JsonValue::String(a), JsonValue::String(b)) => date::compare(a, b),
+        (a, b) if rank(a) != rank(b) => rank(a).cmp(&rank(b)),
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+/// Split a string into runs of ascii digits and runs of other characters.
+fn chunks(s: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut digit = None;
+
+    for (i, c) in s.char_indices() {
+        let is_digit = c.is_ascii_digit();
+        if digit.is_some_and(|d| d != is_digit) {
+            chunks.push(&s[start..i]);
+            start = i;
+        }
+
+        digit = Some(is_digit);
+    }
+
+    if start < s.len() {
+        chunks.push(&s[start..]);
+    }
+
+    chunks
+}
+
+/// Compare two strings, runs of digits being compared by their numeric value
+/// so that `item2` comes before `item10`.
+pub(crate)
+fn natural_compare(a: &str, b: &str) -> Ordering {
+    let a_chunks = chunks(a);
+    let b_chunks = chunks(b);
+
+    for (a, b) in a_chunks.iter().zip(b_chunks.iter()) {
+        let is_number = a.starts_with(|c: char| c.is_ascii_digit()) &&
+            b.starts_with(|c: char| c.is_ascii_digit());
+
+        let ord = if is_number {
+            let a_num = a.trim_start_matches('0');
+            let b_num = b.trim_start_matches('0');
+
+            a_num.len().cmp(&b_num.len())
+                .then_with(|| a_num.cmp(b_num))
+                .then_with(|| a.len().cmp(&b.len()))
+        }
+        else {
+            a.cmp(b)
+        };
+
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+
+    a_chunks.len().cmp(&b_chunks.len())
+}
@@ -16,9 +16,19 @@
 
 use {
     crate::{
-        context::JsonContext,
+        context::{
+            ArrayMerge,
+            JsonContext,
+            Merge,
+            NullMerge,
+        },
         error::Error,
-        file::Source,
+        escape::Escape,
+        file::{
+            Source,
+            read_file,
+        },
+        format::Format,
         parser::Parser,
     },
     serde_json::{
@@ -28,7 +38,12 @@ use {
     },
     std::{
         env::current_dir,
+        fs::File,
         path::PathBuf,
+        time::{
+            Duration,
+            UNIX_EPOCH,
+        },
     },
 };
 
@@ -93,7 +108,7 @@ fn json_context_2() {
     assert_eq!(ctx_1_file_1, path_prop);
 
     // should overrite property "path"
-    ctx.read_in(second_ctx).unwrap();
+    ctx.read_in(second_ctx, None, None).unwrap();
 
     let path_prop = ctx.get_path("path").unwrap();
     assert_eq!(ctx_2_file_1, path_prop);
@@ -205,7 +220,7 @@ fn parser_1() {
 #[test]
 fn parser_2() {
     let mut p = Parser::new("test/parser/2/test.txt").unwrap();
-    p.read_ctx_in("ctx.json").unwrap();
+    p.read_ctx_in("ctx.json", None, None).unwrap();
     let ctx = p.ctx().as_ref().unwrap();
     let name = ctx.get("name").unwrap();
     assert_eq!("A name", name.as_str().unwrap());
@@ -398,6 +413,389 @@ fn include_content_9() {
     assert_eq!(against[0..against.len()-1], p.as_output());
 }
 
+#[test]
+fn include_content_10() {
+    let mut p = Parser::new("test/include_content/10/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        "Crème &lt;brûlée&gt; &amp; &quot;café&quot; &#39;ñ&#39;",
+        p.as_output()
+    );
+}
+
+#[test]
+fn include_content_11() {
+    let mut p = Parser::new("test/include_content/11/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("a&#x20;b&#x22;ç&#x3D;é", p.as_output());
+}
+
+#[test]
+fn include_content_12() {
+    let mut p = Parser::new("test/include_content/12/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("caf%C3%A9%20%26%20cr%C3%A8me%2F%C3%BC%3Fx%3D1", p.as_output());
+}
+
+#[test]
+fn include_content_13() {
+    let mut p = Parser::new("test/include_content/13/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        "it\\x27s\\x20\\x22ñ\\x22\\x0A\\x3C\\x2Fscript\\x3E\\u2028",
+        p.as_output()
+    );
+}
+
+#[test]
+fn include_content_14() {
+    let mut p = Parser::new("test/include_content/14/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Zoë,\"Müller, \"\"Jr.\"\"\"", p.as_output());
+}
+
+#[test]
+fn include_content_15() {
+    let mut p = Parser::new("test/include_content/15/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("echo 'it'\\''s ünïcode $HOME'", p.as_output());
+}
+
+#[test]
+fn include_content_16() {
+    let mut p = Parser::new("test/include_content/16/file.html").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "<a href=\"/search?q=fish%20%26%20chips%2F%C3%BC\" ",
+            "title=\"fish&#x20;&#x26;&#x20;chips&#x2F;ü\">FISH &amp; CHIPS/Ü</a>",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn include_content_17() {
+    let mut p = Parser::new("test/include_content/17/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        "Ünïcödé 👩‍👩‍👧…\nÜnïcödé 👩‍👩‍👧 family\nÜNÏ",
+        p.as_output()
+    );
+}
+
+#[test]
+fn include_content_18() {
+    let mut p = Parser::new("test/include_content/18/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "Élan vital: the über-test\n",
+            "Élan Vital: The Über-Test\n",
+            "élan-vital-the-über-test",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn include_content_19() {
+    let mut p = Parser::new("test/include_content/19/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("007|Zoë..|  Zoë|", p.as_output());
+}
+
+#[test]
+fn include_content_20() {
+    let mut p = Parser::new("test/include_content/20/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("ïve\ncafécafé\nnaïve\nnaïve café", p.as_output());
+}
+
+#[test]
+fn include_content_21() {
+    let mut p = Parser::new("test/include_content/21/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("4\nHello, wörld!\\nSecond line.", p.as_output());
+}
+
+#[test]
+fn include_content_22() {
+    let mut p = Parser::new("test/include_content/22/empty-pad.txt").unwrap();
+    if let Err(Error::IllegalArgument(name, arg, c, _)) = p.parse() {
+        assert_eq!("pad-left", name);
+        assert_eq!("", arg);
+        assert_eq!(0, c.line());
+        assert_eq!(36, c.position());
+    }
+    else {
+        panic!("Should have returned IllegalArgument error.");
+    }
+
+    let mut p = Parser::new("test/include_content/22/bad-number.txt").unwrap();
+    if let Err(Error::IllegalCharacter(name, ch, _, _)) = p.parse() {
+        assert_eq!("include-content truncate-modifier", name);
+        assert_eq!('x', ch);
+    }
+    else {
+        panic!("Should have returned IllegalCharacter error.");
+    }
+}
+
+#[test]
+fn include_content_23() {
+    let mut p = Parser::new("test/include_content/23/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "19.90\n",
+            "3|2|4\n",
+            "-1.3|-1.3|-1.2|-1\n",
+            "0.00000010|0.13|19",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn include_content_24() {
+    let mut p = Parser::new("test/include_content/24/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "1,234,567|-9,876,543.22|-9.876.543,22|999\n",
+            "46%|45.7%|45.6%\n",
+            "512 B|1.5 KiB|5 GiB||",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn include_content_25() {
+    let mut p = Parser::new("test/include_content/25/string.txt").unwrap();
+    if let Err(Error::ValueNotNumber(a)) = p.parse() {
+        assert_eq!("name", a.to_string());
+    }
+    else {
+        panic!("Should have returned ValueNotNumber error.");
+    }
+
+    let mut p = Parser::new("test/include_content/25/bool.txt").unwrap();
+    if let Err(Error::ValueNotNumber(a)) = p.parse() {
+        assert_eq!("flag", a.to_string());
+    }
+    else {
+        panic!("Should have returned ValueNotNumber error.");
+    }
+
+    let mut p = Parser::new("test/include_content/25/bad-mode.txt").unwrap();
+    if let Err(Error::IllegalArgument(name, arg, c, _)) = p.parse() {
+        assert_eq!("fixed", name);
+        assert_eq!("sideways", arg);
+        assert_eq!(0, c.line());
+        assert_eq!(33, c.position());
+    }
+    else {
+        panic!("Should have returned IllegalArgument error.");
+    }
+}
+
+#[test]
+fn include_content_26() {
+    let mut p = Parser::new("test/include_content/26/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "RUST, TEMPLATES, WEB\n",
+            "3|5|0\n",
+            "rust|Web|templates||\n",
+            "templates+web|web templates rust|olléH\n",
+            "3/1.5//true|[\"rust\",\"templates\"]||",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn include_content_27() {
+    let mut p = Parser::new("test/include_content/27/file.txt").unwrap();
+    if let Err(Error::ValueNotArray(a)) = p.parse() {
+        assert_eq!("title", a.to_string());
+    }
+    else {
+        panic!("Should have returned ValueNotArray error.");
+    }
+}
+
+#[test]
+fn include_content_28() {
+    let mut p = Parser::new("test/include_content/28/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Fish &amp; FISH &amp; CHIPS", p.as_output());
+}
+
+#[test]
+fn include_content_29() {
+    let mut p = Parser::new("test/include_content/29/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("--abcabcabc 2.500", p.as_output());
+
+    let mut p = Parser::new("test/include_content/29/repeat.txt").unwrap();
+    if let Err(Error::IllegalArgument(name, arg, _, _)) = p.parse() {
+        assert_eq!("repeat", name);
+        assert_eq!("9000000000000000000", arg);
+    }
+    else {
+        panic!("Should have returned IllegalArgument error.");
+    }
+
+    for (file, name, arg, position) in [
+        ("fixed", "fixed", "9000000000000000000", 32),
+        ("percent", "percent", "5000", 34),
+        ("pad", "pad-left", "99999999999", 35),
+    ] {
+        let mut p = Parser::new(format!("test/include_content/29/{file}.txt")).unwrap();
+        if let Err(Error::IllegalArgument(n, a, c, _)) = p.parse() {
+            assert_eq!(name, n);
+            assert_eq!(arg, a);
+            assert_eq!(0, c.line());
+            assert_eq!(position, c.position());
+        }
+        else {
+            panic!("Should have returned IllegalArgument error.");
+        }
+    }
+}
+
+#[test]
+fn date_1() {
+    let mut p = Parser::new("test/date/1/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "March 3, 2026\n",
+            "Tue Mar 03 26 09:05:07 +02:00|9:05 AM\n",
+            "2026-03-03 00:00:00 +0000|060 Thursday 1709251199 25 %",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn date_2() {
+    let mut p = Parser::new("test/date/2/file.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("abcdef", p.as_output());
+}
+
+#[test]
+fn date_3() {
+    let mut p = Parser::new("test/date/3/now.txt").unwrap();
+    p.set_now("2026-03-04T12:00:00Z").unwrap();
+    p.parse().unwrap();
+    assert_eq!("2026-03-04 before", p.as_output());
+
+    let mut p = Parser::new("test/date/3/no-context.txt").unwrap();
+    p.set_now("2026-03-03").unwrap();
+    p.parse().unwrap();
+    assert_eq!("2026-03-03", p.as_output());
+
+    // the build values are virtual and never written to the context
+    let mut p = Parser::new("test/date/3/root.txt").unwrap();
+    p.set_now("2026-03-03").unwrap();
+    p.parse().unwrap();
+    let output = p.as_output();
+    assert!(output.starts_with("2026-03-03{"));
+    assert!(!output.contains("$build"));
+
+    let mut p = Parser::new("test/date/3/now.txt").unwrap();
+    if let Err(Error::IllegalDate(d)) = p.set_now("March 4th") {
+        assert_eq!("March 4th", d);
+    }
+    else {
+        panic!("Should have returned IllegalDate error.");
+    }
+
+    let mut p = Parser::new("test/date/3/not-a-date.txt").unwrap();
+    if let Err(Error::ValueNotDate(a)) = p.parse() {
+        assert_eq!("name", a.to_string());
+    }
+    else {
+        panic!("Should have returned ValueNotDate error.");
+    }
+
+    let mut p = Parser::new("test/date/3/bad-format.txt").unwrap();
+    if let Err(Error::IllegalArgument(name, arg, c, _)) = p.parse() {
+        assert_eq!("date", name);
+        assert_eq!("%F %Q", arg);
+        assert_eq!(0, c.line());
+        assert_eq!(29, c.position());
+    }
+    else {
+        panic!("Should have returned IllegalArgument error.");
+    }
+}
+
+#[test]
+fn html_escape_1() {
+    let mut p = Parser::new("test/html_escape/1/page.html").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "<p>&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; &quot;more&quot;</p>\n",
+            "<p><script>alert('x')</script> & \"more\"</p>",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn html_escape_2() {
+    let mut p = Parser::new("test/html_escape/2/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("<p><script>alert('x')</script> & \"more\"</p>", p.as_output());
+}
+
+#[test]
+fn html_escape_2_override() {
+    let mut p = Parser::new("test/html_escape/2/page.arcana").unwrap();
+    p.set_escape(Escape::Html);
+    p.parse().unwrap();
+    assert_eq!(
+        "<p>&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; &quot;more&quot;</p>",
+        p.as_output()
+    );
+}
+
+#[test]
+fn html_escape_3() {
+    let mut p = Parser::new("test/html_escape/3/page.html").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "<script>var data = {\"name\":\"\\u003c/script\\u003e\\u003cb\\u003eBold ",
+            "\\u0026 \\u0027quoted\\u0027\\u003c/b\\u003e\"};</script>\n",
+            "<script>var raw = {\"name\":\"</script><b>Bold & 'quoted'</b>\"};</script>",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn html_escape_4() {
+    let mut p = Parser::new("test/html_escape/4/page.html").unwrap();
+    p.parse().unwrap();
+    assert_eq!("<h1>Fish &amp; Chips</h1><h2>Fish &amp; Chips</h2>", p.as_output());
+}
+
+#[test]
+fn html_escape_5() {
+    let mut p = Parser::new("test/html_escape/5/page.html").unwrap();
+    p.parse().unwrap();
+    assert_eq!("<main><p>&lt;i&gt;</p></main>", p.as_output());
+}
+
 #[test]
 fn include_file_1() {
     let mut p = Parser::new("test/include_file/1/file1.txt").unwrap();
@@ -581,41 +979,164 @@ fn for_file_5() {
 }
 
 #[test]
-fn for_item_1() {
-    let mut p = Parser::new("test/for_item/1/for.txt").unwrap();
+fn for_file_6() {
+    let mut p = Parser::new("test/for_file/6/for-file.arcana").unwrap();
     p.parse().unwrap();
-    assert_eq!("First\nSecond\nThird\nEnd.", p.as_output());
+    assert_eq!(
+        concat!(
+            "File 2, File 3, File 4\n",
+            "5, 4\n",
+            "File 1+File 2 / File 3+File 4 / File 5",
+        ),
+        p.as_output(),
+    );
 }
 
 #[test]
-fn for_item_2() {
-    let mut p = Parser::new("test/for_item/2/for.txt").unwrap();
+fn for_file_7() {
+    let mut p = Parser::new("test/for_file/7/for-file.arcana").unwrap();
     p.parse().unwrap();
-    assert_eq!("First\nSecond\nThird\nEnd.", p.as_output());
-}
-
-#[test]
-fn for_item_3() {
-    let mut p1 = Parser::new("test/for_item/3/one.arcana").unwrap();
-    p1.parse().unwrap();
-    assert_eq!("First, Second", p1.as_output());
-
-    let mut p2 = Parser::new("test/for_item/3/two.arcana").unwrap();
-    p2.parse().unwrap();
-    assert_eq!("", p2.as_output());
+    assert_eq!(
+        concat!(
+            "Article a before b.txt\n",
+            "Article b after a before c.txt\n",
+            "Article c after b\n",
+        ),
+        p.as_output(),
+    );
 }
 
 #[test]
-fn for_item_4() {
-    let mut p = Parser::new("test/for_item/4/for-item.arcana").unwrap();
+fn for_file_8() {
+    let mut p = Parser::new("test/for_file/8/for-file.arcana").unwrap();
     p.parse().unwrap();
-    assert_eq!("First, Second, Third", p.as_output());
+    assert_eq!(
+        concat!(
+            "a.md, b.txt, drafts/e.md, sub/c.md, sub/deep/d.md\n",
+            "a.md, b.txt, drafts/e.md, sub/c.md\n",
+            "a.md, sub/c.md, sub/deep/d.md\n",
+            "drafts/e.md, sub/c.md\n",
+            "sub/deep, sub, drafts\n",
+            "a.md, b.txt, drafts, sub",
+        ),
+        p.as_output(),
+    );
 }
 
 #[test]
-fn full_1() {
-    let mut p = Parser::new("test/full/1/page.html").unwrap();
-    p.parse().unwrap();
+fn for_file_9() {
+    let modified = [
+        ("item1.txt", 1704067200),
+        ("item2.txt", 1709251200),
+        ("item10.txt", 1706745600),
+    ];
+
+    for (name, secs) in modified {
+        File::options()
+            .write(true)
+            .open(format!("test/for_file/9/files/{name}"))
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    let mut p = Parser::new("test/for_file/9/for-file.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "item1, item2, item10\n",
+            "item1, item10, item2\n",
+            "item1 5 B, item10 3 B, item2 1 B\n",
+            "item2 2024-03-01, item10 2024-02-01\n",
+            "item10, item2, item1",
+        ),
+        p.as_output(),
+    );
+}
+
+#[test]
+fn for_item_1() {
+    let mut p = Parser::new("test/for_item/1/for.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("First\nSecond\nThird\nEnd.", p.as_output());
+}
+
+#[test]
+fn for_item_2() {
+    let mut p = Parser::new("test/for_item/2/for.txt").unwrap();
+    p.parse().unwrap();
+    assert_eq!("First\nSecond\nThird\nEnd.", p.as_output());
+}
+
+#[test]
+fn for_item_3() {
+    let mut p1 = Parser::new("test/for_item/3/one.arcana").unwrap();
+    p1.parse().unwrap();
+    assert_eq!("First, Second", p1.as_output());
+
+    let mut p2 = Parser::new("test/for_item/3/two.arcana").unwrap();
+    p2.parse().unwrap();
+    assert_eq!("", p2.as_output());
+}
+
+#[test]
+fn for_item_4() {
+    let mut p = Parser::new("test/for_item/4/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("First, Second, Third", p.as_output());
+}
+
+#[test]
+fn for_item_5() {
+    let mut p = Parser::new("test/for_item/5/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "-4,1.5,2,10,33\n",
+            "33,10,2,1.5,-4\n",
+            "Item3,item1,item10,item2\n",
+            "Item3,item1,item2,item10\n",
+            "2026-01-15 00:00,2026-03-03 10:00,2026-03-03 08:30\n",
+            "33,10,2,1.5,-4",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn for_item_6() {
+    let mut p = Parser::new("test/for_item/6/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "Dana, Bo, Cy, Ari\n",
+            "Ari, Cy, Dana, Bo\n",
+            "Dana, Cy, Bo, Ari\n",
+            "Bo, Dana, Cy, Ari",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn for_item_7() {
+    let mut p = Parser::new("test/for_item/7/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "One, Three, Four of 3\n",
+            "One, Four\n",
+            "Two, Three, Five\n",
+            "None.",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn full_1() {
+    let mut p = Parser::new("test/full/1/page.html").unwrap();
+    p.parse().unwrap();
     assert_eq!(
         concat!(
             "<!DOCTYPE html>\n",
@@ -974,3 +1495,740 @@ fn copy_path_1() {
 
     assert_eq!("", p.as_output());
 }
+
+#[test]
+fn for_item_8() {
+    let mut p = Parser::new("test/for_item/8/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "1, 2, 3\n",
+            "6, 7 of 2\n",
+            "3, 4\n",
+            "7, 6\n",
+            "1,2,3 / 4,5,6 / 7\n",
+            "None.",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/for_item/8/zero.arcana").unwrap();
+    if let Err(Error::IllegalArgument(name, arg, c, _)) = p.parse() {
+        assert_eq!("batch", name);
+        assert_eq!("0", arg);
+        assert_eq!(1, c.line());
+        assert_eq!(20, c.position());
+    }
+    else {
+        panic!("Should have returned IllegalArgument error.");
+    }
+}
+
+#[test]
+fn for_item_9() {
+    let mut p = Parser::new("test/for_item/9/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "1. fr: Français, 2. en: English, 3. de: Deutsch\n",
+            "de, en, fr\n",
+            "Deutsch, Français\n",
+            "fr, de of 2\n",
+            "None.",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/for_item/9/array.arcana").unwrap();
+    if let Err(Error::ValueNotObject(a)) = p.parse() {
+        assert_eq!("list", a.to_string());
+    }
+    else {
+        panic!("Should have returned ValueNotObject error.");
+    }
+}
+
+#[test]
+fn for_item_10() {
+    let mut p = Parser::new("test/for_item/10/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "1 [2] 3 4\n",
+            "***\n",
+            "0, 3, 6, 9 of 4\n",
+            "5, 3, 1, -1\n",
+            "4",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/for_item/10/not-number.arcana").unwrap();
+    if let Err(Error::ValueNotNumber(a)) = p.parse() {
+        assert_eq!("title", a.to_string());
+    }
+    else {
+        panic!("Should have returned ValueNotNumber error.");
+    }
+
+    let mut p = Parser::new("test/for_item/10/zero-step.arcana").unwrap();
+    if let Err(Error::IllegalArgument(name, arg, c, _)) = p.parse() {
+        assert_eq!("range", name);
+        assert_eq!("0..4..0", arg);
+        assert_eq!(0, c.line());
+        assert_eq!(7, c.position());
+    }
+    else {
+        panic!("Should have returned IllegalArgument error.");
+    }
+
+    for (file, range) in [
+        ("too-long", "0..9000000000000000000"),
+        ("full", "-9223372036854775808..9223372036854775807"),
+    ] {
+        let mut p = Parser::new(format!("test/for_item/10/{file}.arcana")).unwrap();
+        if let Err(Error::IllegalArgument(name, arg, c, _)) = p.parse() {
+            assert_eq!("range", name);
+            assert_eq!(range, arg);
+            assert_eq!(0, c.line());
+            assert_eq!(7, c.position());
+        }
+        else {
+            panic!("Should have returned IllegalArgument error.");
+        }
+    }
+}
+
+#[test]
+fn for_item_11() {
+    let mut p = Parser::new("test/for_item/11/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "news: Hello, Update; food: Recipes, Soup; misc: Draft\n",
+            "2024 (2); 2023 (2)\n",
+            "Guide: 2; Reference: 1\n",
+            "Reference: 1; Guide: 2",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/for_item/11/not-date.arcana").unwrap();
+    if let Err(Error::ValueNotDate(a)) = p.parse() {
+        assert_eq!("title", a.to_string());
+    }
+    else {
+        panic!("Should have returned ValueNotDate error.");
+    }
+}
+
+#[test]
+fn for_item_12() {
+    let mut p = Parser::new("test/for_item/12/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "First: next second\n",
+            "Second: prev first next third\n",
+            "Third: prev second\n",
+            "a1@0.0 a2@0.1\n",
+            "b1@1.0 b2@1.1\n",
+            "111121211221",
+        ),
+        p.as_output(),
+    );
+}
+
+#[test]
+fn for_item_13() {
+    let mut p = Parser::new("test/for_item/13/for-item.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "2026-03-03, 2026-03-03T00:00:00Z, 2026-03-03T01:00:00Z, 2026-03-03T10:00:00+05:00, a, b\n",
+            "b, a, 2026-03-03T10:00:00+05:00, 2026-03-03T01:00:00Z, 2026-03-03T00:00:00Z, 2026-03-03",
+        ),
+        p.as_output()
+    );
+}
+
+#[test]
+fn loop_control_1() {
+    let mut p = Parser::new("test/loop_control/1/loop-control.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "acd\n",
+            "a-b-c\n",
+            "a, b, c\n",
+            " 2a \n",
+            "File 1; File 2; File 3\n",
+            "!{break}",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/loop_control/1/outside.arcana").unwrap();
+    if let Err(Error::IllegalLoopControl(name, c, _)) = p.parse() {
+        assert_eq!("break", name);
+        assert_eq!(1, c.line());
+        assert_eq!(7, c.position());
+    }
+    else {
+        panic!("Should have returned IllegalLoopControl error.");
+    }
+}
+
+#[test]
+fn macro_1() {
+    let mut p = Parser::new("test/macro/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "Hello, world!\n",
+            "Hello, Ada!\n",
+            "<a href=\"/p/1/\" class=\"plain\">Read One</a>\n",
+            "<a href=\"/about/\" class=\"nav\">About</a>\n",
+            "[a][b]\n",
+            "?{greet}",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/macro/1/unknown.arcana").unwrap();
+    if let Err(Error::UnknownMacro(name, c, _)) = p.parse() {
+        assert_eq!("nope", name);
+        assert_eq!(0, c.line());
+        assert_eq!(0, c.position());
+    }
+    else {
+        panic!("Should have returned UnknownMacro error.");
+    }
+
+    let mut p = Parser::new("test/macro/1/unknown-param.arcana").unwrap();
+    if let Err(Error::UnknownParameter(name, param, c, _)) = p.parse() {
+        assert_eq!("greet", name);
+        assert_eq!("whom", param);
+        assert_eq!(1, c.line());
+        assert_eq!(8, c.position());
+    }
+    else {
+        panic!("Should have returned UnknownParameter error.");
+    }
+
+    let mut p = Parser::new("test/macro/1/missing-param.arcana").unwrap();
+    if let Err(Error::MissingParameter(name, param, c, _)) = p.parse() {
+        assert_eq!("badge", name);
+        assert_eq!("text", param);
+        assert_eq!(1, c.line());
+        assert_eq!(0, c.position());
+    }
+    else {
+        panic!("Should have returned MissingParameter error.");
+    }
+}
+
+#[test]
+fn block_1() {
+    let mut p = Parser::new("test/block/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "<title>Post | Site | Blog</title>\n",
+            "Hello, Empty!\n",
+            "Blog footer",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/block/1/layout.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "<title>Site</title>\n",
+            "Empty\n",
+            "Footer",
+        ),
+        p.as_output(),
+    );
+}
+
+#[test]
+fn params_1() {
+    let mut p = Parser::new("test/params/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Hi 3 2", p.as_output());
+
+    let mut p = Parser::new("test/params/1/card.arcana").unwrap();
+    if let Err(Error::MissingTemplateParameter(param, t, c, f)) = p.parse() {
+        assert_eq!("title", param);
+        assert!(t.ends_with("test/params/1/card.arcana"));
+        assert!(f.ends_with("test/params/1/card.arcana"));
+        assert_eq!(0, c.line());
+        assert_eq!(0, c.position());
+    }
+    else {
+        panic!("Should have returned MissingTemplateParameter error.");
+    }
+
+    let mut p = Parser::new("test/params/1/missing.arcana").unwrap();
+    if let Err(Error::MissingTemplateParameter(param, t, c, f)) = p.parse() {
+        assert_eq!("title", param);
+        assert!(t.ends_with("test/params/1/card.arcana"));
+        assert!(f.ends_with("test/params/1/missing.arcana"));
+        assert_eq!(0, c.line());
+        assert_eq!(0, c.position());
+    }
+    else {
+        panic!("Should have returned MissingTemplateParameter error.");
+    }
+
+    let mut p = Parser::new("test/params/1/wrong-type.arcana").unwrap();
+    if let Err(Error::TemplateParameterType(param, ty, t, c, f)) = p.parse() {
+        assert_eq!("title", param);
+        assert_eq!("string", ty);
+        assert!(t.ends_with("test/params/1/card.arcana"));
+        assert!(f.ends_with("test/params/1/wrong-type.arcana"));
+        assert_eq!(1, c.line());
+        assert_eq!(0, c.position());
+    }
+    else {
+        panic!("Should have returned TemplateParameterType error.");
+    }
+
+    let mut p = Parser::new("test/params/1/bad-path.arcana").unwrap();
+    if let Err(Error::TemplateParameterType(param, ty, _, c, _)) = p.parse() {
+        assert_eq!("link", param);
+        assert_eq!("path", ty);
+        assert_eq!(1, c.line());
+    }
+    else {
+        panic!("Should have returned TemplateParameterType error.");
+    }
+
+    let mut p = Parser::new("test/params/1/child.arcana").unwrap();
+    if let Err(Error::MissingTemplateParameter(param, t, c, f)) = p.parse() {
+        assert_eq!("heading", param);
+        assert!(t.ends_with("test/params/1/layout.arcana"));
+        assert!(f.ends_with("test/params/1/child.arcana"));
+        assert_eq!(0, c.line());
+        assert_eq!(0, c.position());
+    }
+    else {
+        panic!("Should have returned MissingTemplateParameter error.");
+    }
+}
+
+#[test]
+fn schema_1() {
+    let mut p = Parser::new("test/schema/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Hello 2", p.as_output());
+
+    let mut p = Parser::new("test/schema/1/bad.arcana").unwrap();
+    if let Err(Error::SourceSchemaViolation(v, d, c, f)) = p.parse() {
+        assert_eq!(
+            vec![
+                ("/title".to_owned(), "must have a length of at least 1".to_owned()),
+                ("/tags/1".to_owned(), "must be of type string".to_owned()),
+                ("/count".to_owned(), "must be at least 0".to_owned()),
+                ("/extra".to_owned(), "is not allowed".to_owned()),
+            ],
+            v,
+        );
+        assert!(d.ends_with("test/schema/1/bad.json"));
+        assert!(f.ends_with("test/schema/1/bad.arcana"));
+        assert_eq!(1, c.line());
+        assert_eq!(0, c.position());
+    }
+    else {
+        panic!("Should have returned SourceSchemaViolation error.");
+    }
+
+    let mut p = Parser::new("test/schema/1/unchecked.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("", p.as_output());
+
+    let mut p = Parser::new("test/schema/1/bypass.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("ok", p.as_output());
+
+    let mut p = Parser::new("test/schema/1/unchecked.arcana").unwrap();
+    p.set_context_schema("test/schema/1/schema.json").unwrap();
+    if let Err(Error::SourceSchemaViolation(v, _, c, _)) = p.parse() {
+        assert_eq!(4, v.len());
+        assert_eq!(0, c.line());
+    }
+    else {
+        panic!("Should have returned SourceSchemaViolation error.");
+    }
+
+    let mut curr = current_dir().unwrap();
+    curr.push("test/schema/1/bad.json");
+    if let Err(Error::SchemaViolation(v, _)) = JsonContext::read_with_schema(curr, "test/schema/1/schema.json") {
+        assert_eq!(4, v.len());
+    }
+    else {
+        panic!("Should have returned SchemaViolation error.");
+    }
+
+    let mut curr = current_dir().unwrap();
+    curr.push("test/schema/1/post.json");
+    for (schema, r) in [ ("cycle", "#"), ("mutual", "#/definitions/a") ] {
+        let schema = format!("test/schema/1/{schema}.json");
+        if let Err(Error::SchemaViolation(v, _)) = JsonContext::read_with_schema(&curr, schema) {
+            assert_eq!(vec![ ("".to_owned(), format!("has a circular reference {r:?}")) ], v);
+        }
+        else {
+            panic!("Should have returned SchemaViolation error.");
+        }
+    }
+}
+
+#[test]
+fn format_1() {
+    let mut p = Parser::new("test/format/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "My Site Ada Home About \n",
+            "[Widget: 5][Gadget \"X\": 10]\n",
+            "Yaml Page 3\n",
+            "Partial for Ada",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/format/1/bad.arcana").unwrap();
+    if let Err(Error::SourceParse(format, _, line, f)) = p.parse() {
+        assert_eq!("Toml", format);
+        assert_eq!(2, line);
+        assert!(f.ends_with("test/format/1/data/bad.toml"));
+    }
+    else {
+        panic!("Should have returned SourceParse error.");
+    }
+
+    let mut p = Parser::new("test/format/1/rows.arcana").unwrap();
+    if let Err(Error::NotAMap(f)) = p.parse() {
+        assert!(f.ends_with("test/format/1/data/rows.csv"));
+    }
+    else {
+        panic!("Should have returned NotAMap error.");
+    }
+
+    let mut p = Parser::new("test/format/1/unnamed.arcana").unwrap();
+    if let Err(Error::IllegalCharacter(tag, ch, c, _)) = p.parse() {
+        assert_eq!("source", tag);
+        assert_eq!('}', ch);
+        assert_eq!(0, c.line());
+        assert_eq!(29, c.position());
+    }
+    else {
+        panic!("Should have returned IllegalCharacter error.");
+    }
+
+    let mut p = Parser::new("test/format/1/bypass.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("ok", p.as_output());
+
+    let mut p = Parser::new("test/format/1/bom.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Ada 36", p.as_output());
+}
+
+#[test]
+fn format_2() {
+    let path = "test/format/1/data/site.toml";
+    let toml = Format::from_path(path).parse(path, read_file(path).unwrap()).unwrap();
+    assert_eq!(
+        json!({
+            "title": "My Site",
+            "url": "https://example.com",
+            "year": 2024,
+            "ratio": 1.5,
+            "published": "1979-05-27T07:32:00Z",
+            "draft": false,
+            "partial": "./partial.arcana",
+            "author": { "name": "Ada", "e-mail": "ada@example.com" },
+            "nav": [
+                { "label": "Home", "href": "/" },
+                { "label": "About", "href": "/about/" },
+            ],
+            "meta": {
+                "tags": [ "a", "b" ],
+                "description": "A site about things.",
+                "point": { "x": 1, "y": { "z": 2 } },
+            },
+        }),
+        toml,
+    );
+
+    let path = "test/format/1/data/page.txt";
+    let yaml = Format::Yaml.parse(path, read_file(path).unwrap()).unwrap();
+    assert_eq!(
+        json!({
+            "title": "Yaml Page",
+            "count": 3,
+            "ok": true,
+            "empty": null,
+            "version": "1.2.0",
+            "tags": [ "one", "two # not a comment" ],
+            "people": [
+                { "name": "Ada", "role": "admin" },
+                { "name": "Bob", "role": "user's" },
+            ],
+            "flow": [ 1, "two", { "a": "b" } ],
+            "body": "Line one\n\nLine two\n",
+            "folded": "Folded text",
+        }),
+        yaml,
+    );
+
+    let path = "test/format/1/data/rows.csv";
+    let csv = Format::from_path(path).parse(path, read_file(path).unwrap()).unwrap();
+    assert_eq!(
+        json!([
+            { "name": "Widget", "price": "5", "note": "Has, comma" },
+            { "name": "Gadget \"X\"", "price": "10", "note": "" },
+        ]),
+        csv,
+    );
+}
+
+#[test]
+fn front_matter_1() {
+    let mut p = Parser::new("test/front_matter/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "Page\n",
+            "Second 2024-02-03\n",
+            "First 2024-01-02\n",
+            "Body of Second\n",
+            "Page",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/front_matter/1/posts/a.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Body of First", p.as_output());
+
+    if let Err(Error::SourceParse(format, _, line, _)) = Parser::new("test/front_matter/1/bad.arcana") {
+        assert_eq!("Toml", format);
+        assert_eq!(2, line);
+    }
+    else {
+        panic!("Should have returned SourceParse error.");
+    }
+}
+
+#[test]
+fn front_matter_2() {
+    let mut p = Parser::new("test/front_matter/2/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Child: From the layout", p.as_output());
+}
+
+#[test]
+fn source_dir_1() {
+    let mut p = Parser::new("test/source_dir/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "Staff\n",
+            "Ada Writes about engines.\n",
+            "Bob Edits everything.\n",
+            "Cy",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/source_dir/1/files.arcana").unwrap();
+    if let Err(Error::DuplicateContextKey(key, p1, p2)) = p.parse() {
+        assert_eq!("a", key);
+        assert!(p1.ends_with("test/source_dir/1/dupes/files/a.json"));
+        assert!(p2.ends_with("test/source_dir/1/dupes/files/a.toml"));
+    }
+    else {
+        panic!("Should have returned DuplicateContextKey error.");
+    }
+
+    let mut p = Parser::new("test/source_dir/1/dirs.arcana").unwrap();
+    if let Err(Error::DuplicateContextKey(key, p1, p2)) = p.parse() {
+        assert_eq!("b", key);
+        assert!(p1.ends_with("test/source_dir/1/dupes/dirs/b"));
+        assert!(p2.ends_with("test/source_dir/1/dupes/dirs/b.json"));
+    }
+    else {
+        panic!("Should have returned DuplicateContextKey error.");
+    }
+
+    let mut p = Parser::new("test/source_dir/1/dotted.arcana").unwrap();
+    if let Err(Error::IllegalContextKey(key, path)) = p.parse() {
+        assert_eq!("site.local", key);
+        assert!(path.ends_with("test/source_dir/1/dotted/site.local.json"));
+    }
+    else {
+        panic!("Should have returned IllegalContextKey error.");
+    }
+
+    let mut p = Parser::new("test/source_dir/1/replaced.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Ada Writes about ships.", p.as_output());
+}
+
+#[test]
+fn merge_1() {
+    let mut p = Parser::new("test/merge/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "Site Ada fr final\n",
+            "Home Apropos Blog \n",
+            "abc\n",
+            "Header Footer",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/merge/1/append.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "Site fr\n",
+            "Home About Apropos Blog \n",
+            "abc",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/merge/1/replace.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("Apropos Blog \nc", p.as_output());
+
+    let mut p = Parser::new("test/merge/1/bad.arcana").unwrap();
+    if let Err(Error::IllegalArgument(name, arg, c, _)) = p.parse() {
+        assert_eq!("merge", name);
+        assert_eq!("key:", arg);
+        assert_eq!(1, c.line());
+        assert_eq!(30, c.position());
+    }
+    else {
+        panic!("Should have returned IllegalArgument error.");
+    }
+
+    let mut p = Parser::new("test/merge/1/unterminated.arcana").unwrap();
+    if let Err(Error::UnterminatedTag(name, c, _)) = p.parse() {
+        assert_eq!("source", name);
+        assert_eq!(0, c.line());
+        assert_eq!(0, c.position());
+    }
+    else {
+        panic!("Should have returned UnterminatedTag error.");
+    }
+
+    let mut p = Parser::new("test/merge/1/bypass.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("ok", p.as_output());
+
+    let curr = current_dir().unwrap();
+    let mut ctx = JsonContext::read(curr.join("test/merge/1/data/site.json")).unwrap();
+    let merge = Merge { arrays: ArrayMerge::Replace, nulls: NullMerge::Keep, };
+    ctx.read_in_merged::<_, &str>(curr.join("test/merge/1/local/site.json"), None, None, None, &merge)
+        .unwrap();
+    assert_eq!(&JsonValue::Null, ctx.get("meta.draft").unwrap());
+    assert!(ctx.get("meta").unwrap().as_object().unwrap().contains_key("draft"));
+}
+
+#[test]
+fn alias_index_1() {
+    let mut p = Parser::new("test/alias_index/1/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        concat!(
+            "First Last Second Second\n",
+            "no no no\n",
+            "Bonjour Hello Bonjour\n",
+            "Last post First post\n",
+            "Replaced",
+        ),
+        p.as_output(),
+    );
+
+    let mut p = Parser::new("test/alias_index/1/bad.arcana").unwrap();
+    if let Err(Error::UnterminatedAlias(c, _)) = p.parse() {
+        assert_eq!(0, c.line());
+        assert_eq!(2, c.position());
+    }
+    else {
+        panic!("Should have returned UnterminatedAlias error.");
+    }
+
+    let mut p = Parser::new("test/alias_index/1/set.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!(
+        r#"[{"name":"First"},{"name":"Middle"},{"name":"Final"}]"#,
+        p.as_output(),
+    );
+
+    for (file, alias) in [ ("out-of-range", "items.7.name"), ("not-index", "items.name") ] {
+        let mut p = Parser::new(format!("test/alias_index/1/{file}.arcana")).unwrap();
+        if let Err(Error::UnresolvedAlias(a)) = p.parse() {
+            assert_eq!(alias, a.to_string());
+        }
+        else {
+            panic!("Should have returned UnresolvedAlias error.");
+        }
+    }
+}
+
+#[test]
+fn macro_2() {
+    for (file, name) in [ ("super", "super"), ("block", "block") ] {
+        let mut p = Parser::new(format!("test/macro/2/{file}.arcana")).unwrap();
+        if let Err(Error::ReservedMacroName(n, c, _)) = p.parse() {
+            assert_eq!(name, n);
+            assert_eq!(0, c.line());
+            assert_eq!(8, c.position());
+        }
+        else {
+            panic!("Should have returned ReservedMacroName error.");
+        }
+    }
+
+    let mut p = Parser::new("test/macro/2/recursive.arcana").unwrap();
+    if let Err(Error::MacroDepth(name, depth, _, _)) = p.parse() {
+        assert_eq!("m", name);
+        assert_eq!(64, depth);
+    }
+    else {
+        panic!("Should have returned MacroDepth error.");
+    }
+
+    let mut p = Parser::new("test/macro/2/nested.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("hi", p.as_output());
+}
+
+#[test]
+fn macro_3() {
+    let mut p = Parser::new("test/macro/3/page.arcana").unwrap();
+    p.parse().unwrap();
+    assert_eq!("hi", p.as_output());
+    assert!(!PathBuf::from("test/macro/3/side.txt").exists());
+    assert!(PathBuf::from("test/macro/3/page.arcana").exists());
+
+    for (file, import) in [ ("self", "self"), ("a", "a") ] {
+        let mut p = Parser::new(format!("test/macro/3/{file}.arcana")).unwrap();
+        if let Err(Error::CircularImport(i, c, _)) = p.parse() {
+            assert!(i.ends_with(format!("test/macro/3/{import}.arcana")));
+            assert_eq!(0, c.line());
+            assert_eq!(0, c.position());
+        }
+        else {
+            panic!("Should have returned CircularImport error.");
+        }
+    }
+}
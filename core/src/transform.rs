@@ -0,0 +1,169 @@
+//! String transformations for the Arcana Templating Engine.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Shorten a value to at most `len` graphemes, appending `suffix` if any were
+/// removed.
+pub(crate)
+fn truncate<S>(value: S, len: usize, suffix: &str) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let mut graphemes = value.graphemes(true);
+    let output = graphemes.by_ref().take(len).collect::<String>();
+
+    if graphemes.next().is_none() {
+        return output;
+    }
+
+    format!("{output}{suffix}")
+}
+
+/// Uppercase the first character of a value.
+pub(crate)
+fn capitalize<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    let mut chars = value.as_ref().chars();
+
+    match chars.next() {
+        Some(c) => format!("{}{}", c.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+/// Uppercase the first character of every word of a value.
+pub(crate)
+fn title<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    value.as_ref().split_word_bounds()
+        .map(capitalize)
+        .collect::<String>()
+}
+
+/// Lowercase a value and join its alphanumeric runs with hyphens.
+pub(crate)
+fn slugify<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    let mut output = String::new();
+    let mut hyphen = false;
+
+    for c in value.as_ref().chars() {
+        if c.is_alphanumeric() {
+            if hyphen && !output.is_empty() {
+                output.push('-');
+            }
+
+            hyphen = false;
+            output.extend(c.to_lowercase());
+        }
+        else {
+            hyphen = true;
+        }
+    }
+
+    output
+}
+
+fn padding(width: usize, len: usize, pad: &str) -> String {
+    pad.graphemes(true)
+        .cycle()
+        .take(width.saturating_sub(len))
+        .collect::<String>()
+}
+
+/// Pad the start of a value with `pad` until it is `width` graphemes long.
+pub(crate)
+fn pad_left<S>(value: S, width: usize, pad: &str) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let len = value.graphemes(true).count();
+
+    format!("{}{value}", padding(width, len, pad))
+}
+
+/// Pad the end of a value with `pad` until it is `width` graphemes long.
+pub(crate)
+fn pad_right<S>(value: S, width: usize, pad: &str) -> String
+where
+    S: AsRef<str>
+{
+    let value = value.as_ref();
+    let len = value.graphemes(true).count();
+
+    format!("{value}{}", padding(width, len, pad))
+}
+
+/// Take `len` graphemes of a value beginning at the grapheme at `start`.
+pub(crate)
+fn substring<S>(value: S, start: usize, len: usize) -> String
+where
+    S: AsRef<str>
+{
+    value.as_ref().graphemes(true)
+        .skip(start)
+        .take(len)
+        .collect::<String>()
+}
+
+/// Count the graphemes within a value.
+pub(crate)
+fn length<S>(value: S) -> usize
+where
+    S: AsRef<str>
+{
+    value.as_ref().graphemes(true).count()
+}
+
+/// Reverse the graphemes of a value.
+pub(crate)
+fn reverse<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    value.as_ref().graphemes(true)
+        .rev()
+        .collect::<String>()
+}
+
+/// Count the words within a value.
+pub(crate)
+fn wordcount<S>(value: S) -> usize
+where
+    S: AsRef<str>
+{
+    value.as_ref().unicode_words().count()
+}
+
+/// Replace line breaks within a value with their escape sequences.
+pub(crate)
+fn escape_newlines<S>(value: S) -> String
+where
+    S: AsRef<str>
+{
+    value.as_ref()
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}